@@ -207,6 +207,88 @@ fn check_prog(name: &str, args: &[&str]) -> bool {
     }
 }
 
+/// Returns the toolchain prefix of the tool, such as `arm-linux-gnueabihf-` for
+/// `arm-linux-gnueabihf-gcc`, the directory of the tool is kept.
+///
+/// Returns `None` if the tool is a bare compiler name like `cc` or `/usr/bin/clang`.
+fn tool_prefix(tool: &str) -> Option<String> {
+    let (dir, file) = match tool.rfind(|c| c == '/' || c == '\\') {
+        Some(pos) => tool.split_at(pos + 1),
+        None => ("", tool),
+    };
+    let pos = ["-gcc", "-clang", "-cc", "-ld"]
+        .iter()
+        .filter_map(|name| file.rfind(name))
+        .max()?;
+    if pos == 0 {
+        None
+    } else {
+        Some(format!("{}{}-", dir, &file[..pos]))
+    }
+}
+
+/// Derive the `--cross-prefix` for the configure.
+///
+/// The `CROSS_PREFIX` is used as is if specified, otherwise the prefix is derived
+/// from the linker or the C compiler, and falls back to the target triple.
+fn cross_prefix(
+    target: &str,
+    linker: Option<&str>,
+    cross_prefix: Option<&str>,
+    cc: Option<&str>,
+) -> String {
+    if let Some(prefix) = cross_prefix.filter(|v| !v.is_empty()) {
+        return prefix.to_string();
+    }
+    linker
+        .and_then(tool_prefix)
+        .or_else(|| cc.and_then(tool_prefix))
+        .unwrap_or_else(|| format!("{}-", target))
+}
+
+/// Configure the cross compiling for iOS with the Xcode toolchain.
+///
+/// The SDK is located by `SDKROOT` or `xcrun`, and the minimum iOS version is
+/// read from the `IPHONEOS_DEPLOYMENT_TARGET`.
+fn configure_ios(configure: &mut Command) {
+    let target = env::var("TARGET").unwrap();
+    let arch = match env::var("CARGO_CFG_TARGET_ARCH").unwrap().as_str() {
+        "aarch64" => "arm64".to_string(),
+        arch => arch.to_string(),
+    };
+    let simulator = target.ends_with("-sim") || arch == "x86_64" || arch == "x86";
+    let sdk = if simulator {
+        "iphonesimulator"
+    } else {
+        "iphoneos"
+    };
+    let sysroot = env::var("SDKROOT").unwrap_or_else(|_| {
+        let output = Command::new("xcrun")
+            .args(&["--sdk", sdk, "--show-sdk-path"])
+            .output()
+            .expect("xcrun failed, install the Xcode or set the SDKROOT");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    });
+    let min_version = env::var("IPHONEOS_DEPLOYMENT_TARGET").unwrap_or_else(|_| "10.0".to_string());
+    let min_version_flag = if simulator {
+        "-mios-simulator-version-min"
+    } else {
+        "-miphoneos-version-min"
+    };
+    let flags = format!(
+        "-arch {} -isysroot {} {}={}",
+        arch, sysroot, min_version_flag, min_version
+    );
+
+    configure.arg("--enable-cross-compile");
+    configure.arg("--target-os=darwin");
+    configure.arg(format!("--arch={}", arch));
+    configure.arg(format!("--sysroot={}", sysroot));
+    configure.arg("--cc=clang");
+    configure.arg(format!("--extra-cflags={}", flags));
+    configure.arg(format!("--extra-ldflags={}", flags));
+}
+
 fn build() -> io::Result<()> {
     // make sure the `make` exists
     if !check_prog("make", &["--version"]) {
@@ -220,15 +302,23 @@ fn build() -> io::Result<()> {
     configure.current_dir(&source());
     configure.arg(format!("--prefix={}", search().to_string_lossy()));
 
-    if env::var("TARGET").unwrap() != env::var("HOST").unwrap() {
+    if env::var("CARGO_CFG_TARGET_OS").unwrap() == "ios" {
+        configure_ios(&mut configure);
+    } else if env::var("TARGET").unwrap() != env::var("HOST").unwrap() {
         let target = env::var("TARGET").unwrap();
-        let linker = env::var("RUSTC_LINKER").unwrap();
-        if linker.contains(&target) {
-            configure.arg(format!("--cross-prefix={}-", target));
-        } else {
-            let (target, _) = &linker.split_at(linker.rfind('-').unwrap());
-            configure.arg(format!("--cross-prefix={}-", target));
-        }
+        let cc = env::var(format!("CC_{}", target.replace('-', "_")))
+            .or_else(|_| env::var("TARGET_CC"))
+            .or_else(|_| env::var("CC"))
+            .ok();
+        configure.arg(format!(
+            "--cross-prefix={}",
+            cross_prefix(
+                &target,
+                env::var("RUSTC_LINKER").ok().as_deref(),
+                env::var("CROSS_PREFIX").ok().as_deref(),
+                cc.as_deref(),
+            )
+        ));
         configure.arg(format!(
             "--arch={}",
             env::var("CARGO_CFG_TARGET_ARCH").unwrap()
@@ -2467,115 +2557,87 @@ fn check_features(
     include_paths: Vec<PathBuf>,
     infos: &[(&'static str, Option<&'static str>, &'static str)],
 ) {
-    let mut includes_code = String::new();
-    let mut main_code = String::new();
+    // The probe invokes the C compiler, which is unavailable in some sandboxed
+    // environments. Skipping it emits no `FF_API_*` cfgs at all, so the
+    // bindings behave as if none of the macros were defined by the headers.
+    if env::var("CARGO_FEATURE_NO_FEATURE_PROBE").is_ok()
+        || env::var("FFAV_SKIP_FEATURE_CHECK").is_ok()
+    {
+        return;
+    }
 
+    let enabled = |feature: Option<&str>| {
+        feature.map_or(true, |feature| {
+            env::var(format!("CARGO_FEATURE_{}", feature.to_uppercase())).is_ok()
+        })
+    };
+
+    // The values of the macros are detected by preprocessing only, instead of
+    // compiling and running a program, so the target compiler and headers can
+    // be used when cross compiling. Each macro is evaluated by `#if` into a
+    // marker like `ffav_check__ff_api_xxx__11`, the digits are the value and
+    // whether it's defined. The markers are lowercase so they're not expanded.
+    let mut code = String::new();
     for &(header, feature, var) in infos {
-        if let Some(feature) = feature {
-            if env::var(format!("CARGO_FEATURE_{}", feature.to_uppercase())).is_err() {
-                continue;
-            }
+        if !enabled(feature) {
+            continue;
         }
 
-        let include = format!("#include <{}>", header);
-        if includes_code.find(&include).is_none() {
-            includes_code.push_str(&include);
-            includes_code.push_str(&"\n");
+        let include = format!("#include <{}>\n", header);
+        if code.find(&include).is_none() {
+            code.push_str(&include);
         }
-        includes_code.push_str(&format!(
+        code.push_str(&format!(
             r#"
             #ifndef {var}
-            #define {var} 0
-            #define {var}_is_defined 0
+            ffav_check__{marker}__00
+            #elif {var}
+            ffav_check__{marker}__11
             #else
-            #define {var}_is_defined 1
+            ffav_check__{marker}__01
             #endif
         "#,
-            var = var
-        ));
-
-        main_code.push_str(&format!(
-            r#"printf("[{var}]%d%d\n", {var}, {var}_is_defined);"#,
-            var = var
+            var = var,
+            marker = var.to_lowercase()
         ));
     }
 
-    // let version_check_info = [("avcodec", 56, 60, 0, 80)];
-    // for &(lib, begin_version_major, end_version_major, begin_version_minor, end_version_minor) in
-    //     version_check_info.iter()
-    // {
-    //     for version_major in begin_version_major..end_version_major {
-    //         for version_minor in begin_version_minor..end_version_minor {
-    //             main_code.push_str(&format!(
-    //                 r#"printf("[{lib}_version_greater_than_{version_major}_{version_minor}]%d\n", LIB{lib_uppercase}_VERSION_MAJOR > {version_major} || (LIB{lib_uppercase}_VERSION_MAJOR == {version_major} && LIB{lib_uppercase}_VERSION_MINOR > {version_minor}));"#,
-    //                 lib = lib,
-    //                 lib_uppercase = lib.to_uppercase(),
-    //                 version_major = version_major,
-    //                 version_minor = version_minor
-    //             ));
-    //         }
-    //     }
-    // }
-
     let out_dir = output();
 
     write!(
         File::create(out_dir.join("check.c")).expect("Failed to create file"),
-        r#"
-            #include <stdio.h>
-            {includes_code}
-
-            int main()
-            {{
-                {main_code}
-                return 0;
-            }}
-           "#,
-        includes_code = includes_code,
-        main_code = main_code
+        "{}",
+        code
     )
     .expect("Write failed");
 
-    let executable = out_dir.join(if cfg!(windows) { "check.exe" } else { "check" });
-    let mut compiler = cc::Build::new()
-        .target(&env::var("HOST").unwrap())
-        .get_compiler()
-        .to_command();
+    let mut compiler = cc::Build::new().get_compiler().to_command();
 
     for dir in include_paths {
         compiler.arg("-I");
         compiler.arg(dir.to_string_lossy().into_owned());
     }
-    if !compiler
+    let output = compiler
         .current_dir(&out_dir)
-        .arg("-o")
-        .arg(&executable)
+        .arg("-E")
         .arg("check.c")
-        .status()
-        .expect("Command failed")
-        .success()
-    {
-        panic!("Compile failed");
-    }
-
-    let stdout_raw = Command::new(out_dir.join(&executable))
-        .current_dir(&out_dir)
         .output()
-        .expect("Check failed")
-        .stdout;
-    let stdout = str::from_utf8(stdout_raw.as_slice()).unwrap();
-
-    println!("stdout={}", stdout);
+        .expect("Command failed");
+    if !output.status.success() {
+        panic!(
+            "Preprocess failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let stdout = str::from_utf8(output.stdout.as_slice()).unwrap();
 
     for &(_, feature, var) in infos {
-        if let Some(feature) = feature {
-            if env::var(format!("CARGO_FEATURE_{}", feature.to_uppercase())).is_err() {
-                continue;
-            }
+        if !enabled(feature) {
+            continue;
         }
 
-        let var_str = format!("[{var}]", var = var);
-        let pos = stdout.find(&var_str).expect("Variable not found in output") + var_str.len();
+        let marker = format!("ffav_check__{}__", var.to_lowercase());
+        let pos = stdout.find(&marker).expect("Variable not found in output") + marker.len();
         if &stdout[pos..pos + 1] == "1" {
             println!(r#"cargo:rustc-cfg=feature="{}""#, var.to_lowercase());
             println!(r#"cargo:{}=true"#, var.to_lowercase());
@@ -2591,32 +2653,6 @@ fn check_features(
             println!(r#"cargo:{}_is_defined=true"#, var.to_lowercase());
         }
     }
-
-    // for &(lib, begin_version_major, end_version_major, begin_version_minor, end_version_minor) in
-    //     version_check_info.iter()
-    // {
-    //     for version_major in begin_version_major..end_version_major {
-    //         for version_minor in begin_version_minor..end_version_minor {
-    //             let search_str = format!(
-    //                 "[{lib}_version_greater_than_{version_major}_{version_minor}]",
-    //                 version_major = version_major,
-    //                 version_minor = version_minor,
-    //                 lib = lib
-    //             );
-    //             let pos = stdout
-    //                 .find(&search_str)
-    //                 .expect("Variable not found in output")
-    //                 + search_str.len();
-
-    //             if &stdout[pos..pos + 1] == "1" {
-    //                 println!(
-    //                     r#"cargo:rustc-cfg=feature="{}""#,
-    //                     &search_str[1..(search_str.len() - 1)]
-    //                 );
-    //             }
-    //         }
-    //     }
-    // }
 }
 
 fn search_include(include_paths: &[PathBuf], header: &str) -> String {
@@ -2647,11 +2683,37 @@ fn link_to_libraries(statik: bool) {
             println!("cargo:rustc-link-lib={}={}", ffmpeg_ty, lib.name);
         }
     }
-    if env::var("CARGO_FEATURE_BUILD_ZLIB").is_ok() && cfg!(target_os = "linux") {
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
+    let need_zlib = if env::var("CARGO_FEATURE_BUILD_ZLIB").is_ok() {
+        target_os == "linux" || target_os == "macos"
+    } else {
+        // The zlib is autodetected by the configure and always available on macOS,
+        // but the static libraries don't carry the `-lz` with them.
+        statik
+            && target_os == "macos"
+            && env::var("CARGO_FEATURE_DISABLE_ZLIB").is_err()
+            && extralibs_requires("z").unwrap_or(true)
+    };
+    if need_zlib {
         println!("cargo:rustc-link-lib=z");
     }
 }
 
+/// Returns true if any `EXTRALIBS` in the `config.mak` links to the `lib`.
+///
+/// Returns `None` if the FFmpeg was not built by us.
+fn extralibs_requires(lib: &str) -> Option<bool> {
+    let file = File::open(source().join("ffbuild/config.mak")).ok()?;
+    let flag = format!("-l{}", lib);
+    Some(
+        BufReader::new(file)
+            .lines()
+            .filter_map(|line| line.ok())
+            .filter(|line| line.starts_with("EXTRALIBS"))
+            .any(|line| line.split(|c| c == '=' || c == ' ').any(|v| v == flag)),
+    )
+}
+
 fn link_libs_for_module(module: &str) {
     let config_mak = source().join("ffbuild/config.mak");
     let file = File::open(config_mak).unwrap();
@@ -2664,11 +2726,13 @@ fn link_libs_for_module(module: &str) {
             continue;
         }
         let linker_args = line.split('=').last().unwrap().split(' ');
-        let include_libs = linker_args
-            .filter(|v| v.starts_with("-l"))
-            .map(|flag| &flag[2..]);
-        for lib in include_libs {
-            println!("cargo:rustc-link-lib={}", lib);
+        for arg in linker_args {
+            // external libraries like the libzimg may be installed outside the system paths
+            if let Some(dir) = arg.strip_prefix("-L") {
+                println!("cargo:rustc-link-search=native={}", dir);
+            } else if let Some(lib) = arg.strip_prefix("-l") {
+                println!("cargo:rustc-link-lib={}", lib);
+            }
         }
     }
 }
@@ -2681,12 +2745,12 @@ fn main() {
             "cargo:rustc-link-search=native={}",
             search().join("lib").to_string_lossy()
         );
-        link_to_libraries(statik);
         if fs::metadata(&search().join("lib").join("libavutil.a")).is_err() {
             fs::create_dir_all(&output()).expect("failed to create build directory");
             fetch().unwrap();
             build().unwrap();
         }
+        link_to_libraries(statik);
 
         // Check additional required libraries.
         {
@@ -2782,7 +2846,8 @@ fn main() {
         all_paths
     };
 
-    if statik && cfg!(target_os = "macos") {
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
+    if statik && target_os == "macos" {
         let frameworks = vec![
             "AppKit",
             "AudioToolbox",
@@ -2806,6 +2871,22 @@ fn main() {
         }
     }
 
+    if statik && target_os == "ios" {
+        let frameworks = vec![
+            "AudioToolbox",
+            "AVFoundation",
+            "CoreFoundation",
+            "CoreMedia",
+            "CoreVideo",
+            "Foundation",
+            "Security",
+            "VideoToolbox",
+        ];
+        for f in frameworks {
+            println!("cargo:rustc-link-lib=framework={}", f);
+        }
+    }
+
     check_features(
         include_paths.clone(),
         &[
@@ -2832,6 +2913,7 @@ fn main() {
             ("libavutil/avutil.h", None, "FF_API_PKT_PTS"),
             ("libavutil/avutil.h", None, "FF_API_ERROR_FRAME"),
             ("libavutil/avutil.h", None, "FF_API_FRAME_QP"),
+            ("libavutil/avutil.h", None, "FF_API_OLD_CHANNEL_LAYOUT"),
             (
                 "libavcodec/avcodec.h",
                 Some("avcodec"),
@@ -3121,6 +3203,8 @@ fn main() {
         .ctypes_prefix("libc")
         // https://github.com/servo/rust-bindgen/issues/550
         .blacklist_type("max_align_t")
+        // Implemented by hand to skip the raw plane pointers.
+        .no_debug("AVFrame")
         .rustified_enum("*")
         .prepend_enum_name(false)
         .derive_eq(true)
@@ -3138,6 +3222,14 @@ fn main() {
             .header(search_include(&include_paths, "libavcodec/vorbis_parser.h"));
     }
 
+    // The VA-API hwcontext requires the `va/va.h` from libva
+    if env::var("CARGO_FEATURE_VAAPI").is_ok() {
+        builder = builder.header(search_include(
+            &include_paths,
+            "libavutil/hwcontext_vaapi.h",
+        ));
+    }
+
     if env::var("CARGO_FEATURE_AVDEVICE").is_ok() {
         builder = builder.header(search_include(&include_paths, "libavdevice/avdevice.h"));
     }
@@ -3239,3 +3331,46 @@ fn main() {
         .write_to_file(output().join("bindings.rs"))
         .expect("Couldn't write bindings!");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cross_prefix() {
+        let target = "armv7-unknown-linux-gnueabihf";
+        assert_eq!(
+            cross_prefix(target, Some("arm-linux-gnueabihf-gcc"), None, None),
+            "arm-linux-gnueabihf-"
+        );
+        assert_eq!(
+            cross_prefix(
+                target,
+                Some("/opt/rpi/bin/arm-linux-gnueabihf-gcc"),
+                None,
+                None
+            ),
+            "/opt/rpi/bin/arm-linux-gnueabihf-"
+        );
+        assert_eq!(
+            cross_prefix(target, Some("aarch64-linux-gnu-gcc-10"), None, None),
+            "aarch64-linux-gnu-"
+        );
+        assert_eq!(
+            cross_prefix(target, Some("cc"), None, Some("arm-linux-gnueabihf-gcc")),
+            "arm-linux-gnueabihf-"
+        );
+        assert_eq!(
+            cross_prefix(target, Some("/usr/bin/clang"), Some("arm-none-"), None),
+            "arm-none-"
+        );
+        assert_eq!(
+            cross_prefix(target, Some("gcc"), None, Some("clang")),
+            "armv7-unknown-linux-gnueabihf-"
+        );
+        assert_eq!(
+            cross_prefix(target, None, None, None),
+            "armv7-unknown-linux-gnueabihf-"
+        );
+    }
+}
@@ -28,12 +28,11 @@
  */
 use ffav_sys::*;
 use std::env;
-use std::ffi::{CStr, CString};
+use std::ffi::CString;
 
 fn main() {
     unsafe {
         let mut fmt_ctx: *mut AVFormatContext = std::ptr::null_mut();
-        let mut tag: *mut AVDictionaryEntry = std::ptr::null_mut();
         let mut ret;
 
         let args = env::args().collect::<Vec<_>>();
@@ -62,22 +61,10 @@ fn main() {
             panic!("avformat_find_stream_info({:?}) = {}", fmt_ctx, ret);
         }
 
-        let dummy = CString::new("").unwrap();
-        loop {
-            tag = av_dict_get(
-                (*fmt_ctx).metadata,
-                dummy.as_ptr(),
-                tag,
-                AV_DICT_IGNORE_SUFFIX,
-            );
-            if tag.is_null() {
-                break;
+        if let Some(metadata) = (*fmt_ctx).metadata() {
+            for (key, value) in metadata.iter() {
+                println!("{}={}", key, value);
             }
-            println!(
-                "{:?}={:?}",
-                CStr::from_ptr((*tag).key),
-                CStr::from_ptr((*tag).value)
-            );
         }
 
         avformat_close_input(&mut fmt_ctx);
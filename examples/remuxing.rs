@@ -96,17 +96,14 @@ fn main() {
 
             av_dump_format(ifmt_ctx_ptr, 0, in_filename.as_ptr(), 0);
 
-            avformat_alloc_output_context2(
-                &mut ofmt_ctx_ptr,
-                std::ptr::null_mut(),
-                std::ptr::null_mut(),
-                out_filename.as_ptr(),
-            );
-            if ofmt_ctx_ptr.is_null() {
-                println!("Could not create output context");
-                ret = AVERROR_UNKNOWN;
-                break 'outer;
-            }
+            ofmt_ctx_ptr = match alloc_output_context(None, Some(&args[2])) {
+                Ok(v) => v,
+                Err(e) => {
+                    println!("Could not create output context");
+                    ret = e.0;
+                    break 'outer;
+                }
+            };
 
             let ifmt_ctx = &mut *ifmt_ctx_ptr;
             let ofmt_ctx = &mut *ofmt_ctx_ptr;
@@ -115,27 +112,21 @@ fn main() {
             let in_streams: &[*mut AVStream] =
                 std::slice::from_raw_parts(ifmt_ctx.streams, in_nb_streams);
 
-            let mut stream_index = 0;
-            let mut stream_mapping: Vec<i32> = Vec::with_capacity(in_nb_streams);
-            stream_mapping.resize(stream_mapping.capacity(), -1);
+            let stream_mapping = StreamMapping::new(ifmt_ctx, |codec_type| {
+                codec_type == AVMEDIA_TYPE_AUDIO
+                    || codec_type == AVMEDIA_TYPE_VIDEO
+                    || codec_type == AVMEDIA_TYPE_SUBTITLE
+            });
 
             ofmt_ptr = ofmt_ctx.oformat;
 
             for i in 0..in_nb_streams {
-                let in_stream = &mut *in_streams[i];
-                let in_codecpar_ptr: *mut AVCodecParameters = in_stream.codecpar;
-                let in_codecpar = &mut *in_codecpar_ptr;
-
-                if in_codecpar.codec_type != AVMEDIA_TYPE_AUDIO
-                    && in_codecpar.codec_type != AVMEDIA_TYPE_VIDEO
-                    && in_codecpar.codec_type != AVMEDIA_TYPE_SUBTITLE
-                {
-                    stream_mapping[i] = -1;
+                if stream_mapping.map(i).is_none() {
                     continue;
                 }
 
-                stream_mapping[i] = stream_index;
-                stream_index += 1;
+                let in_stream = &mut *in_streams[i];
+                let in_codecpar_ptr: *mut AVCodecParameters = in_stream.codecpar;
 
                 let out_stream_ptr = avformat_new_stream(ofmt_ctx_ptr, std::ptr::null_mut());
                 if out_stream_ptr.is_null() {
@@ -151,7 +142,7 @@ fn main() {
                     println!("Failed to copy codec parameters");
                     break 'outer;
                 }
-                (*out_stream.codecpar).codec_tag = 0;
+                (*out_stream.codecpar).set_codec_tag(0);
             }
 
             av_dump_format(ofmt_ctx_ptr, 0, out_filename.as_ptr(), 1);
@@ -175,7 +166,7 @@ fn main() {
                 ofmt_ctx.nb_streams.try_into().unwrap(),
             );
 
-            let mut cur_pts: [i64; 64] = [0; 64];
+            let mut pts_gen = PtsGenerator::new();
 
             'inner: loop {
                 ret = av_read_frame(ifmt_ctx_ptr, &mut pkt);
@@ -184,27 +175,23 @@ fn main() {
                 }
 
                 let curr_stream_index = pkt.stream_index as usize;
+                let out_stream_index = match stream_mapping.map(curr_stream_index) {
+                    Some(v) => v,
+                    None => {
+                        av_packet_unref(&mut pkt);
+                        continue;
+                    }
+                };
+
+                pkt.stream_index = out_stream_index as i32;
                 let in_stream_ptr = in_streams[curr_stream_index];
-                if curr_stream_index >= stream_mapping.len()
-                    || stream_mapping[curr_stream_index] < 0
-                {
-                    av_packet_unref(&mut pkt);
-                    continue;
-                }
-
-                pkt.stream_index = stream_mapping[curr_stream_index];
-                let out_stream_ptr = out_streams[curr_stream_index];
+                let out_stream_ptr = out_streams[out_stream_index];
 
                 let in_stream = &mut *in_stream_ptr;
                 let out_stream = &mut *out_stream_ptr;
 
-                let orig_pts = pkt.pts;
-                let orig_duration = pkt.duration;
-
-                if orig_pts == AV_NOPTS_VALUE {
-                    pkt.pts = cur_pts[curr_stream_index];
-                    pkt.dts = pkt.pts;
-                }
+                let duration = pkt.duration;
+                pts_gen.next(curr_stream_index, &mut pkt, duration);
 
                 log_packet(ifmt_ctx_ptr, &pkt, "in");
 
@@ -232,10 +219,6 @@ fn main() {
                     break 'inner;
                 }
 
-                if orig_pts == AV_NOPTS_VALUE {
-                    cur_pts[curr_stream_index] += orig_duration;
-                }
-
                 av_packet_unref(&mut pkt);
             }
 
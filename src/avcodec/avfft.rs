@@ -0,0 +1,69 @@
+use crate::{av_dct_calc, av_dct_end, av_dct_init, DCTContext, DCTTransformType};
+
+/// A Discrete Cosine Transform context.
+#[derive(Debug)]
+pub struct Dct {
+    ptr: *mut DCTContext,
+    size: usize,
+}
+
+impl Dct {
+    /// Create a new DCT context for `1 << nbits` samples (`(1 << nbits) + 1` for `DCT_I`).
+    ///
+    /// Returns `None` if `nbits` is not in range `4..=16` or the context can't be created.
+    pub fn new(nbits: i32, kind: DCTTransformType) -> Option<Self> {
+        if !(4..=16).contains(&nbits) {
+            return None;
+        }
+        let ptr = unsafe { av_dct_init(nbits, kind) };
+        if ptr.is_null() {
+            return None;
+        }
+        let size = match kind {
+            DCTTransformType::DCT_I => (1 << nbits) + 1,
+            _ => 1 << nbits,
+        };
+        Some(Self { ptr, size })
+    }
+
+    /// Returns the number of samples required by `calc()`.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Transform the data in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `data` is not equal to `size()`.
+    pub fn calc(&mut self, data: &mut [f32]) {
+        assert_eq!(data.len(), self.size, "invalid DCT data length");
+        unsafe { av_dct_calc(self.ptr, data.as_mut_ptr()) }
+    }
+}
+
+impl Drop for Dct {
+    fn drop(&mut self) {
+        unsafe { av_dct_end(self.ptr) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dct() {
+        assert!(Dct::new(3, DCTTransformType::DCT_II).is_none());
+        assert!(Dct::new(17, DCTTransformType::DCT_II).is_none());
+        assert_eq!(Dct::new(4, DCTTransformType::DCT_I).unwrap().size(), 17);
+        assert_eq!(Dct::new(4, DCTTransformType::DST_I).unwrap().size(), 16);
+
+        let mut dct = Dct::new(4, DCTTransformType::DCT_II).unwrap();
+        let mut data = [1.0f32; 16];
+        dct.calc(&mut data);
+        assert!(data[0].abs() > 1.0);
+        assert!(data[1..].iter().all(|v| v.abs() < 1e-3));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{
+    av_bsf_alloc, av_bsf_free, av_bsf_get_by_name, av_bsf_init, av_bsf_iterate,
+    av_bsf_receive_packet, av_bsf_send_packet, av_mallocz, av_packet_ref, AVBSFContext,
+    AVBitStreamFilter, AVCodecID, AVPacket, AvError, OwnedPacket, AVERROR, AVERROR_BSF_NOT_FOUND,
+    AV_INPUT_BUFFER_PADDING_SIZE,
+};
+use libc::{c_char, EINVAL, ENOMEM};
+
+/// Returns an iterator over all registered bitstream filters.
+pub fn bsf_filters() -> impl Iterator<Item = &'static AVBitStreamFilter> {
+    let mut opaque = std::ptr::null_mut();
+    std::iter::from_fn(move || unsafe { av_bsf_iterate(&mut opaque).as_ref() })
+}
+
+/// The bitstream filter context which is freed on drop.
+struct BsfContext {
+    ptr: *mut AVBSFContext,
+}
+
+impl Drop for BsfContext {
+    fn drop(&mut self) {
+        unsafe { av_bsf_free(&mut self.ptr) }
+    }
+}
+
+/// Convert the H.264 or HEVC packet from the length prefixed format of MP4,
+/// such as demuxed from MP4 or MKV, to the Annex-B format with start codes.
+///
+/// The `extradata` is the `avcC` or `hvcC` of the stream, the parameter sets
+/// in it are inserted before the key frames. Returns `EINVAL` for the other
+/// codecs.
+pub fn to_annexb(
+    codec_id: AVCodecID,
+    extradata: &[u8],
+    pkt: &AVPacket,
+) -> Result<OwnedPacket, AvError> {
+    let name: &[u8] = match codec_id {
+        AVCodecID::AV_CODEC_ID_H264 => b"h264_mp4toannexb\0",
+        AVCodecID::AV_CODEC_ID_HEVC => b"hevc_mp4toannexb\0",
+        _ => return Err(AvError(AVERROR(EINVAL))),
+    };
+    unsafe {
+        let filter = av_bsf_get_by_name(name.as_ptr() as *const c_char);
+        if filter.is_null() {
+            return Err(AvError(AVERROR_BSF_NOT_FOUND));
+        }
+        let mut ctx = BsfContext {
+            ptr: std::ptr::null_mut(),
+        };
+        let ret = av_bsf_alloc(filter, &mut ctx.ptr);
+        if ret < 0 {
+            return Err(AvError(ret));
+        }
+
+        let par = &mut *(*ctx.ptr).par_in;
+        par.codec_id = codec_id;
+        if !extradata.is_empty() {
+            let size = extradata.len() + AV_INPUT_BUFFER_PADDING_SIZE as usize;
+            par.extradata = av_mallocz(size) as *mut u8;
+            if par.extradata.is_null() {
+                return Err(AvError(AVERROR(ENOMEM)));
+            }
+            std::ptr::copy_nonoverlapping(extradata.as_ptr(), par.extradata, extradata.len());
+            par.extradata_size = extradata.len() as i32;
+        }
+        let ret = av_bsf_init(ctx.ptr);
+        if ret < 0 {
+            return Err(AvError(ret));
+        }
+
+        let mut input = OwnedPacket::new()?;
+        let ret = av_packet_ref(input.as_mut_ptr(), pkt);
+        if ret < 0 {
+            return Err(AvError(ret));
+        }
+        let ret = av_bsf_send_packet(ctx.ptr, input.as_mut_ptr());
+        if ret < 0 {
+            return Err(AvError(ret));
+        }
+        let mut output = OwnedPacket::new()?;
+        let ret = av_bsf_receive_packet(ctx.ptr, output.as_mut_ptr());
+        if ret < 0 {
+            return Err(AvError(ret));
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_annexb() {
+        let sps = [0x67, 0x64, 0x00, 0x1f];
+        let pps = [0x68, 0xee, 0x3c, 0x80];
+        let mut avcc = vec![0x01, 0x64, 0x00, 0x1f, 0xff, 0xe1, 0x00, sps.len() as u8];
+        avcc.extend_from_slice(&sps);
+        avcc.extend_from_slice(&[0x01, 0x00, pps.len() as u8]);
+        avcc.extend_from_slice(&pps);
+
+        let mut data = vec![0x00, 0x00, 0x00, 0x03, 0x65, 0x88, 0x84];
+        let pkt = AVPacket {
+            data: data.as_mut_ptr(),
+            size: data.len() as i32,
+            ..Default::default()
+        };
+        let out = to_annexb(AVCodecID::AV_CODEC_ID_H264, &avcc, &pkt).unwrap();
+        let out = out.as_bytes();
+        assert!(out.starts_with(&[0x00, 0x00, 0x00, 0x01, 0x67]));
+        assert!(out.windows(sps.len()).any(|v| v == sps));
+        assert!(out.windows(pps.len()).any(|v| v == pps));
+        assert!(out.ends_with(&[0x00, 0x00, 0x01, 0x65, 0x88, 0x84]));
+
+        assert_eq!(
+            to_annexb(AVCodecID::AV_CODEC_ID_VP9, &[], &pkt).unwrap_err(),
+            AvError(AVERROR(EINVAL))
+        );
+    }
+
+    #[test]
+    fn test_bsf_filters() {
+        use std::ffi::CStr;
+
+        let names: Vec<_> = bsf_filters()
+            .map(|v| unsafe { CStr::from_ptr(v.name).to_string_lossy() })
+            .collect();
+        assert!(names.iter().any(|v| v == "null"));
+        assert!(names.iter().any(|v| v == "h264_mp4toannexb"));
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{av_dv_codec_profile2, av_dv_frame_profile, AVDVProfile, AVPixelFormat, AVRational};
+use std::convert::TryInto;
+
+/// Get a DV profile for the provided compressed frame.
+///
+/// Returns `None` if the frame header is too short or unknown.
+pub fn dv_profile_from_frame_header(data: &[u8]) -> Option<&'static AVDVProfile> {
+    let size = data.len().try_into().ok()?;
+    unsafe { av_dv_frame_profile(std::ptr::null(), data.as_ptr(), size).as_ref() }
+}
+
+/// Get a DV profile for the provided stream parameters.
+///
+/// The `rate` is used to select between the 720p50 and 720p60 profiles,
+/// pass `0/0` to pick the first profile matched.
+pub fn dv_profile_by_dimensions(
+    w: i32,
+    h: i32,
+    fmt: AVPixelFormat,
+    rate: AVRational,
+) -> Option<&'static AVDVProfile> {
+    unsafe { av_dv_codec_profile2(w, h, fmt, rate).as_ref() }
+}
+
+impl AVDVProfile {
+    /// Returns the picture width of the profile.
+    #[inline]
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// Returns the picture height of the profile.
+    #[inline]
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Returns the pixel format of the profile.
+    #[inline]
+    pub fn pix_fmt(&self) -> AVPixelFormat {
+        self.pix_fmt
+    }
+
+    /// Returns the total size of one frame in bytes.
+    #[inline]
+    pub fn frame_size(&self) -> usize {
+        self.frame_size as usize
+    }
+
+    /// Returns the time base of the profile, `1/25` for PAL.
+    #[inline]
+    pub fn time_base(&self) -> AVRational {
+        self.time_base
+    }
+
+    /// Returns true if the profile is a 50Hz (PAL) system.
+    #[inline]
+    pub fn is_pal(&self) -> bool {
+        self.dsf != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dv_profile() {
+        assert!(dv_profile_from_frame_header(&[0; 16]).is_none());
+
+        let mut frame = vec![0u8; 144000];
+        frame[3] = 0x80;
+        let profile = dv_profile_from_frame_header(&frame).unwrap();
+        assert_eq!(profile.width(), 720);
+        assert_eq!(profile.height(), 576);
+        assert_eq!(profile.frame_size(), 144000);
+        assert!(profile.is_pal());
+
+        let profile = dv_profile_by_dimensions(
+            720,
+            576,
+            AVPixelFormat::AV_PIX_FMT_YUV420P,
+            AVRational::new(25, 1),
+        )
+        .unwrap();
+        assert_eq!(profile.time_base(), AVRational::new(1, 25));
+        assert_eq!(profile.frame_size(), 144000);
+    }
+}
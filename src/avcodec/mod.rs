@@ -1,10 +1,126 @@
 use crate::{
-    AVCodecContext, AVCodecID, AVMediaType, AVPacket, AVPacketSideData, AVPixelFormat,
-    AVSampleFormat, AV_NOPTS_VALUE,
+    AVCodec, AVCodecContext, AVCodecID, AVCodecParameters, AVCodecTag, AVMediaType, AVPacket,
+    AVPacketSideData, AVPacketSideDataType, AVPixelFormat, AVRational, AVSampleFormat, AVStream,
+    AvError, AVERROR, AVERROR_DECODER_NOT_FOUND, AV_NOPTS_VALUE,
 };
+use libc::{c_void, EINVAL, ENOMEM};
 use std::borrow::Cow;
 use std::convert::TryInto;
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+mod avfft;
+pub use self::avfft::*;
+
+mod bsf;
+pub use self::bsf::*;
+
+mod dv_profile;
+pub use self::dv_profile::*;
+
+#[cfg(feature = "vaapi")]
+mod vaapi;
+#[cfg(feature = "vaapi")]
+pub use self::vaapi::*;
+
+mod vorbis_parser;
+pub use self::vorbis_parser::*;
+
+/// Find a registered decoder with the codec `id`.
+pub fn find_decoder(id: AVCodecID) -> Option<&'static AVCodec> {
+    unsafe { crate::avcodec_find_decoder(id).as_ref() }
+}
+
+/// Find a registered encoder with the codec `id`.
+pub fn find_encoder(id: AVCodecID) -> Option<&'static AVCodec> {
+    unsafe { crate::avcodec_find_encoder(id).as_ref() }
+}
+
+/// Find a registered decoder with the specified `name`.
+pub fn find_decoder_by_name(name: &str) -> Option<&'static AVCodec> {
+    let name = CString::new(name).ok()?;
+    unsafe { crate::avcodec_find_decoder_by_name(name.as_ptr()).as_ref() }
+}
+
+/// Find a registered encoder with the specified `name`.
+pub fn find_encoder_by_name(name: &str) -> Option<&'static AVCodec> {
+    let name = CString::new(name).ok()?;
+    unsafe { crate::avcodec_find_encoder_by_name(name.as_ptr()).as_ref() }
+}
+
+/// Returns the codec id of the container `tag`, such as `AV_CODEC_TAG_AVC1`,
+/// in the `tags` tables, `AV_CODEC_ID_NONE` if not found.
+///
+/// Use `AVOutputFormat::codec_id_for_tag()` for the tables of a container.
+///
+/// # Safety
+///
+/// The `tags` must be null or a null terminated list of tables, such as
+/// `AVOutputFormat.codec_tag`, each terminated by an `AV_CODEC_ID_NONE` entry.
+pub unsafe fn codec_tag_to_id(tags: *const *const AVCodecTag, tag: u32) -> AVCodecID {
+    crate::av_codec_get_id(tags, tag)
+}
+
+/// Returns the container tag of the codec `id` in the `tags` tables, 0 if not found.
+///
+/// Use `AVOutputFormat::codec_tag_for_id()` for the tables of a container.
+///
+/// # Safety
+///
+/// The `tags` must be null or a null terminated list of tables, such as
+/// `AVOutputFormat.codec_tag`, each terminated by an `AV_CODEC_ID_NONE` entry.
+pub unsafe fn codec_id_to_tag(tags: *const *const AVCodecTag, id: AVCodecID) -> u32 {
+    crate::av_codec_get_tag(tags, id)
+}
+
+/// Returns the PCM codec id for the sample format `fmt` in the big-endian if
+/// `be` is `Some(true)`, little-endian if `Some(false)` or native if `None`.
+///
+/// Returns `AV_CODEC_ID_NONE` if the sample format has no PCM codec.
+pub fn pcm_codec(fmt: AVSampleFormat, be: Option<bool>) -> AVCodecID {
+    unsafe { crate::av_get_pcm_codec(fmt, be.map_or(-1, |v| v as i32)) }
+}
+
+/// Returns the data of the first side data of the type `ty` in the `list`.
+pub(crate) fn find_side_data(list: &[AVPacketSideData], ty: AVPacketSideDataType) -> Option<&[u8]> {
+    list.iter().find(|v| v.type_ == ty).map(|v| {
+        if v.data.is_null() || v.size <= 0 {
+            &[][..]
+        } else {
+            unsafe { std::slice::from_raw_parts(v.data, v.size as usize) }
+        }
+    })
+}
+
+/// Returns the elements before the `end` of a terminated list, or `&[]` for a null list.
+unsafe fn terminated_slice<'a, T: PartialEq>(ptr: *const T, end: T) -> &'a [T] {
+    if ptr.is_null() {
+        return &[];
+    }
+    let mut len = 0;
+    while *ptr.add(len) != end {
+        len += 1;
+    }
+    std::slice::from_raw_parts(ptr, len)
+}
+
+impl AVCodec {
+    /// Supported sample rates of the audio codec, empty if unknown.
+    pub fn supported_sample_rates(&self) -> &[i32] {
+        unsafe { terminated_slice(self.supported_samplerates, 0) }
+    }
+
+    /// Supported sample formats of the audio codec, empty if unknown.
+    pub fn supported_sample_fmts(&self) -> &[AVSampleFormat] {
+        unsafe { terminated_slice(self.sample_fmts, AVSampleFormat::AV_SAMPLE_FMT_NONE) }
+    }
+
+    /// Supported pixel formats of the video codec, empty if unknown.
+    pub fn supported_pix_fmts(&self) -> &[AVPixelFormat] {
+        unsafe { terminated_slice(self.pix_fmts, AVPixelFormat::AV_PIX_FMT_NONE) }
+    }
+}
 
 impl AVCodecContext {
     /// Some codecs need / can use extradata like Huffman tables.
@@ -26,6 +142,223 @@ impl AVCodecContext {
             }
         }
     }
+
+    /// Set the number of threads used by the codec, `0` to let FFmpeg decide.
+    #[inline]
+    pub fn set_threads(&mut self, count: i32) {
+        self.thread_count = count;
+    }
+
+    /// Let FFmpeg decide the number of threads used by the codec.
+    #[inline]
+    pub fn set_threads_auto(&mut self) {
+        self.set_threads(0);
+    }
+
+    /// Set the multithreading methods allowed to use.
+    ///
+    /// The frame threading adds one frame delay per thread, the slice threading
+    /// requires the codec to support it.
+    pub fn set_thread_type(&mut self, frame: bool, slice: bool) {
+        let mut thread_type = 0;
+        if frame {
+            thread_type |= crate::FF_THREAD_FRAME;
+        }
+        if slice {
+            thread_type |= crate::FF_THREAD_SLICE;
+        }
+        self.thread_type = thread_type;
+    }
+}
+
+/// Allocate a codec context with the default values for the `codec`.
+///
+/// The returned context must be freed with `avcodec_free_context()`.
+pub fn alloc_codec_context(codec: Option<&AVCodec>) -> Result<*mut AVCodecContext, AvError> {
+    let ptr = unsafe { crate::avcodec_alloc_context3(codec.map_or(std::ptr::null(), |v| v)) };
+    if ptr.is_null() {
+        Err(AvError(AVERROR(ENOMEM)))
+    } else {
+        Ok(ptr)
+    }
+}
+
+/// The owned codec context which is freed on drop.
+#[derive(Debug)]
+pub struct OwnedCodecContext {
+    ptr: *mut AVCodecContext,
+}
+
+impl OwnedCodecContext {
+    /// Allocate a codec context with the default values for the `codec`.
+    pub fn new(codec: Option<&AVCodec>) -> Result<Self, AvError> {
+        alloc_codec_context(codec).map(|ptr| Self { ptr })
+    }
+
+    /// Allocate a context for the decoder of the `stream` and fill it with the
+    /// codec parameters of the stream.
+    pub fn from_stream(stream: &AVStream) -> Result<Self, AvError> {
+        let par = stream.codecpar().ok_or_else(|| AvError(AVERROR(EINVAL)))?;
+        let codec = find_decoder(par.codec_id).ok_or(AvError(AVERROR_DECODER_NOT_FOUND))?;
+        let ctx = Self::new(Some(codec))?;
+        let ret = unsafe { crate::avcodec_parameters_to_context(ctx.ptr, par) };
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(ctx)
+        }
+    }
+
+    /// Set the options of the context and the codec private data, such as
+    /// `("preset", "fast")` and `("crf", "23")` for libx264.
+    ///
+    /// All options are applied and the first error is returned with the key
+    /// of the failed option.
+    pub fn configure(&mut self, options: &[(&str, &str)]) -> Result<(), OptionError> {
+        let mut result = Ok(());
+        for &(key, value) in options {
+            let ret = match (CString::new(key), CString::new(value)) {
+                (Ok(key), Ok(value)) => unsafe {
+                    crate::av_opt_set(
+                        self.ptr as *mut c_void,
+                        key.as_ptr(),
+                        value.as_ptr(),
+                        crate::AV_OPT_SEARCH_CHILDREN,
+                    )
+                },
+                _ => AVERROR(EINVAL),
+            };
+            if ret < 0 && result.is_ok() {
+                result = Err(OptionError {
+                    key: key.to_string(),
+                    error: AvError(ret),
+                });
+            }
+        }
+        result
+    }
+
+    /// Returns the raw pointer of the codec context.
+    pub fn as_ptr(&self) -> *const AVCodecContext {
+        self.ptr
+    }
+
+    /// Returns the mutable raw pointer of the codec context.
+    pub fn as_mut_ptr(&mut self) -> *mut AVCodecContext {
+        self.ptr
+    }
+}
+
+/// The error of setting the option `key`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionError {
+    /// The key of the failed option.
+    pub key: String,
+    /// The error returned by FFmpeg.
+    pub error: AvError,
+}
+
+impl fmt::Display for OptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to set option '{}': {}", self.key, self.error)
+    }
+}
+
+impl std::error::Error for OptionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<OptionError> for AvError {
+    fn from(e: OptionError) -> Self {
+        e.error
+    }
+}
+
+impl Deref for OwnedCodecContext {
+    type Target = AVCodecContext;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.ptr }
+    }
+}
+
+impl DerefMut for OwnedCodecContext {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.ptr }
+    }
+}
+
+impl Drop for OwnedCodecContext {
+    fn drop(&mut self) {
+        unsafe { crate::avcodec_free_context(&mut self.ptr) }
+    }
+}
+
+impl AVCodecParameters {
+    /// Extra binary data needed for initializing the decoder, such as the `avcC`.
+    #[inline]
+    pub fn extradata(&self) -> &[u8] {
+        if self.extradata.is_null() || self.extradata_size <= 0 {
+            &[]
+        } else {
+            unsafe { std::slice::from_raw_parts(self.extradata, self.extradata_size as usize) }
+        }
+    }
+
+    /// General type of the encoded data.
+    #[inline]
+    pub fn codec_type(&self) -> AVMediaType {
+        self.codec_type
+    }
+
+    /// Specific type of the encoded data.
+    #[inline]
+    pub fn codec_id(&self) -> AVCodecID {
+        self.codec_id
+    }
+
+    /// Returns the `(width, height)` of the video.
+    #[inline]
+    pub fn dimensions(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    /// Set the fourcc of the codec, 0 to let the muxer choose a compatible one.
+    #[inline]
+    pub fn set_codec_tag(&mut self, tag: u32) {
+        self.codec_tag = tag;
+    }
+}
+
+impl fmt::Display for AVCodecParameters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.codec_type, self.codec_id.get_name())?;
+        match self.codec_type {
+            AVMediaType::AVMEDIA_TYPE_VIDEO => {
+                let fmt = AVPixelFormat::from_raw(self.format);
+                if let Some(fmt) = fmt.filter(|&v| v != AVPixelFormat::AV_PIX_FMT_NONE) {
+                    write!(f, ", {}", fmt.name())?;
+                }
+                if self.width > 0 && self.height > 0 {
+                    write!(f, ", {}x{}", self.width, self.height)?;
+                }
+            }
+            AVMediaType::AVMEDIA_TYPE_AUDIO => {
+                write!(f, ", {} Hz, {} channels", self.sample_rate, self.channels)?;
+                let fmt = AVSampleFormat::from_raw(self.format);
+                if let Some(fmt) = fmt.filter(|&v| v != AVSampleFormat::AV_SAMPLE_FMT_NONE) {
+                    write!(f, ", {}", fmt.name())?;
+                }
+            }
+            _ => {}
+        }
+        if self.bit_rate > 0 {
+            write!(f, ", {} kb/s", self.bit_rate / 1000)?;
+        }
+        Ok(())
+    }
 }
 
 impl Default for AVCodecID {
@@ -52,6 +385,38 @@ impl AVCodecID {
         }
     }
 
+    /// Return codec bits per sample, 0 if the codec has no fixed sample size.
+    pub fn bits_per_coded_sample(self) -> i32 {
+        unsafe { crate::av_get_bits_per_sample(self) }
+    }
+
+    /// Return the name of the `profile` for the given codec, `None` if unknown.
+    pub fn profile_name(self, profile: i32) -> Option<Cow<'static, str>> {
+        unsafe {
+            let name = crate::avcodec_profile_name(self, profile);
+            if name.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(name).to_string_lossy())
+            }
+        }
+    }
+
+    /// Return true if the given codec is a video codec.
+    pub fn is_video(self) -> bool {
+        self.get_type() == AVMediaType::AVMEDIA_TYPE_VIDEO
+    }
+
+    /// Return true if the given codec is an audio codec.
+    pub fn is_audio(self) -> bool {
+        self.get_type() == AVMediaType::AVMEDIA_TYPE_AUDIO
+    }
+
+    /// Return true if the given codec is a subtitle codec.
+    pub fn is_subtitle(self) -> bool {
+        self.get_type() == AVMediaType::AVMEDIA_TYPE_SUBTITLE
+    }
+
     /// Return true if the given codec has GOP props.
     /// # Notes
     /// The types annotations is incomplete.
@@ -59,7 +424,13 @@ impl AVCodecID {
         use AVCodecID::*;
         matches!(
             self,
-            AV_CODEC_ID_H264 | AV_CODEC_ID_HEVC | AV_CODEC_ID_VP8 | AV_CODEC_ID_VP9
+            AV_CODEC_ID_MPEG2VIDEO
+                | AV_CODEC_ID_MPEG4
+                | AV_CODEC_ID_H264
+                | AV_CODEC_ID_HEVC
+                | AV_CODEC_ID_VP8
+                | AV_CODEC_ID_VP9
+                | AV_CODEC_ID_AV1
         )
     }
 }
@@ -108,6 +479,159 @@ impl AVPacket {
     pub fn as_bytes_mut(&mut self) -> &mut [u8] {
         unsafe { std::slice::from_raw_parts_mut(self.data, self.size as usize) }
     }
+
+    /// Returns `true` if the packet contains a key frame.
+    #[inline]
+    pub fn is_keyframe(&self) -> bool {
+        self.flags & crate::AV_PKT_FLAG_KEY != 0
+    }
+
+    /// Set or clear the key frame flag of the packet.
+    #[inline]
+    pub fn set_keyframe(&mut self, yes: bool) {
+        if yes {
+            self.flags |= crate::AV_PKT_FLAG_KEY;
+        } else {
+            self.flags &= !crate::AV_PKT_FLAG_KEY;
+        }
+    }
+
+    /// Returns `true` if the data of the packet is corrupted.
+    #[inline]
+    pub fn is_corrupt(&self) -> bool {
+        self.flags & crate::AV_PKT_FLAG_CORRUPT != 0
+    }
+
+    /// Returns `true` if the packet is required to maintain the decoder state
+    /// but should be discarded after decoding.
+    #[inline]
+    pub fn is_discard(&self) -> bool {
+        self.flags & crate::AV_PKT_FLAG_DISCARD != 0
+    }
+
+    /// Returns the data of the side data of the type `ty`, such as
+    /// `AV_PKT_DATA_NEW_EXTRADATA`, `None` if absent.
+    pub fn side_data_of(&self, ty: AVPacketSideDataType) -> Option<&[u8]> {
+        if self.side_data.is_null() || self.side_data_elems <= 0 {
+            None
+        } else {
+            let list = unsafe {
+                std::slice::from_raw_parts(self.side_data, self.side_data_elems as usize)
+            };
+            find_side_data(list, ty)
+        }
+    }
+
+    /// Create a new reference to the data of the packet, the data is copied if
+    /// the packet is not reference counted.
+    ///
+    /// The returned packet must be released by `av_packet_unref()`, since
+    /// `AVPacket` doesn't free the reference on drop.
+    pub fn clone_ref(&self) -> Option<AVPacket> {
+        let mut pkt = AVPacket::empty();
+        let ret = unsafe { crate::av_packet_ref(&mut pkt, self) };
+        if ret < 0 {
+            None
+        } else {
+            Some(pkt)
+        }
+    }
+
+    /// Ensure the data of the packet is writable, the data is copied if it's
+    /// shared with other references.
+    pub fn make_writable(&mut self) -> Result<(), AvError> {
+        let ret = unsafe { crate::av_packet_make_writable(self) };
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Convert the timestamps and duration of the packet from the `src` time
+    /// base to the `dst` time base, the unknown timestamps are kept.
+    pub fn rescale_ts(&mut self, src: AVRational, dst: AVRational) {
+        unsafe { crate::av_packet_rescale_ts(self, src, dst) }
+    }
+}
+
+/// The owned packet which is freed on drop.
+#[derive(Debug)]
+pub struct OwnedPacket {
+    ptr: *mut AVPacket,
+}
+
+impl OwnedPacket {
+    /// Allocate an empty packet.
+    pub fn new() -> Result<Self, AvError> {
+        let ptr = unsafe { crate::av_packet_alloc() };
+        if ptr.is_null() {
+            Err(AvError(AVERROR(ENOMEM)))
+        } else {
+            Ok(Self { ptr })
+        }
+    }
+
+    /// Returns the raw pointer of the packet.
+    pub fn as_ptr(&self) -> *const AVPacket {
+        self.ptr
+    }
+
+    /// Returns the mutable raw pointer of the packet.
+    pub fn as_mut_ptr(&mut self) -> *mut AVPacket {
+        self.ptr
+    }
+}
+
+impl Deref for OwnedPacket {
+    type Target = AVPacket;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.ptr }
+    }
+}
+
+impl DerefMut for OwnedPacket {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.ptr }
+    }
+}
+
+impl Drop for OwnedPacket {
+    fn drop(&mut self) {
+        unsafe { crate::av_packet_free(&mut self.ptr) }
+    }
+}
+
+/// Synthesize the missing timestamps of packets by the running pts of each stream.
+#[derive(Debug, Clone, Default)]
+pub struct PtsGenerator {
+    next_pts: Vec<i64>,
+}
+
+impl PtsGenerator {
+    /// Create a new generator, the running pts of all streams start from 0.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// If the `pts` of the packet is missing, set both `pts` and `dts` to the
+    /// running pts of the stream, then advance the running pts by `duration`.
+    ///
+    /// A packet with a valid `pts` is left untouched, even if its `dts` is
+    /// missing, and does not change the running pts.
+    pub fn next(&mut self, stream_index: usize, pkt: &mut AVPacket, duration: i64) {
+        if pkt.pts != AV_NOPTS_VALUE {
+            return;
+        }
+        if stream_index >= self.next_pts.len() {
+            self.next_pts.resize(stream_index + 1, 0);
+        }
+        let next_pts = &mut self.next_pts[stream_index];
+        pkt.pts = *next_pts;
+        pkt.dts = pkt.pts;
+        *next_pts += duration;
+    }
 }
 
 impl Default for AVPixelFormat {
@@ -125,6 +649,59 @@ impl Default for AVSampleFormat {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{avcodec_alloc_context3, avcodec_free_context, FF_THREAD_FRAME, FF_THREAD_SLICE};
+
+    #[test]
+    fn test_avcodeccontext_threads() {
+        unsafe {
+            let mut ctx = avcodec_alloc_context3(std::ptr::null());
+            let c = &mut *ctx;
+            c.set_threads(4);
+            assert_eq!(c.thread_count, 4);
+            c.set_threads_auto();
+            assert_eq!(c.thread_count, 0);
+            c.set_thread_type(true, false);
+            assert_eq!(c.thread_type, FF_THREAD_FRAME);
+            c.set_thread_type(false, true);
+            assert_eq!(c.thread_type, FF_THREAD_SLICE);
+            c.set_thread_type(true, true);
+            assert_eq!(c.thread_type, FF_THREAD_FRAME | FF_THREAD_SLICE);
+            c.set_thread_type(false, false);
+            assert_eq!(c.thread_type, 0);
+            avcodec_free_context(&mut ctx);
+        }
+    }
+
+    #[test]
+    fn test_avcodecid_bits_per_coded_sample() {
+        assert_eq!(AVCodecID::AV_CODEC_ID_PCM_S16LE.bits_per_coded_sample(), 16);
+        assert_eq!(AVCodecID::AV_CODEC_ID_PCM_S24BE.bits_per_coded_sample(), 24);
+        assert_eq!(AVCodecID::AV_CODEC_ID_PCM_U8.bits_per_coded_sample(), 8);
+        assert_eq!(AVCodecID::AV_CODEC_ID_H264.bits_per_coded_sample(), 0);
+    }
+
+    #[test]
+    fn test_avcodecid_kind() {
+        use AVCodecID::*;
+        assert!(AV_CODEC_ID_H264.is_video());
+        assert!(!AV_CODEC_ID_H264.is_audio());
+        assert!(AV_CODEC_ID_AAC.is_audio());
+        assert!(AV_CODEC_ID_SUBRIP.is_subtitle());
+        assert!(!AV_CODEC_ID_NONE.is_video());
+        assert_eq!(
+            AV_CODEC_ID_H264
+                .profile_name(crate::FF_PROFILE_H264_HIGH)
+                .unwrap(),
+            "High"
+        );
+        assert!(AV_CODEC_ID_H264.profile_name(-1).is_none());
+        assert!(AV_CODEC_ID_MPEG2VIDEO.has_gop());
+        assert!(AV_CODEC_ID_MPEG4.has_gop());
+        assert!(AV_CODEC_ID_AV1.has_gop());
+        assert!(AV_CODEC_ID_H264.has_gop());
+        assert!(!AV_CODEC_ID_MJPEG.has_gop());
+        assert!(!AV_CODEC_ID_AAC.has_gop());
+    }
 
     #[test]
     fn test_avpacket() {
@@ -134,4 +711,322 @@ mod tests {
         assert_eq!(pkt.as_bytes(), &[]);
         assert_eq!(pkt.as_bytes_mut(), &[]);
     }
+
+    #[test]
+    fn test_pts_generator() {
+        let mut pts_gen = PtsGenerator::new();
+        let mut next = |stream_index: usize, pts: i64, dts: i64, duration: i64| {
+            let mut pkt = AVPacket {
+                pts,
+                dts,
+                ..Default::default()
+            };
+            pts_gen.next(stream_index, &mut pkt, duration);
+            (pkt.pts, pkt.dts)
+        };
+        let nopts = AV_NOPTS_VALUE;
+        assert_eq!(next(0, nopts, nopts, 10), (0, 0));
+        assert_eq!(next(0, nopts, nopts, 10), (10, 10));
+        assert_eq!(next(3, nopts, nopts, 5), (0, 0));
+        assert_eq!(next(0, 100, 90, 10), (100, 90));
+        assert_eq!(next(0, nopts, nopts, 10), (20, 20));
+        assert_eq!(next(0, 120, nopts, 10), (120, nopts));
+        assert_eq!(next(0, nopts, 50, 10), (30, 30));
+        assert_eq!(next(3, nopts, nopts, 5), (5, 5));
+    }
+
+    #[test]
+    fn test_avcodec_supported_formats() {
+        let find_encoder = |name: &str| find_encoder_by_name(name).unwrap();
+
+        let pcm = find_encoder("pcm_s16le");
+        assert_eq!(
+            pcm.supported_sample_fmts(),
+            &[AVSampleFormat::AV_SAMPLE_FMT_S16]
+        );
+        assert!(pcm.supported_sample_rates().is_empty());
+        assert!(pcm.supported_pix_fmts().is_empty());
+
+        let mp2 = find_encoder("mp2");
+        assert!(mp2.supported_sample_rates().contains(&44100));
+        assert!(!mp2.supported_sample_rates().contains(&0));
+
+        let bmp = find_encoder("bmp");
+        assert!(bmp
+            .supported_pix_fmts()
+            .contains(&AVPixelFormat::AV_PIX_FMT_BGR24));
+        assert!(!bmp
+            .supported_pix_fmts()
+            .contains(&AVPixelFormat::AV_PIX_FMT_NONE));
+        assert!(find_encoder("rawvideo").supported_pix_fmts().is_empty());
+    }
+
+    #[test]
+    fn test_find_codec() {
+        use AVCodecID::*;
+
+        let h264 = find_decoder(AV_CODEC_ID_H264).unwrap();
+        assert_eq!(h264.id, AV_CODEC_ID_H264);
+        assert_eq!(
+            find_decoder_by_name("h264").map(|v| v as *const AVCodec),
+            Some(h264 as *const AVCodec)
+        );
+        assert_eq!(
+            find_encoder(AV_CODEC_ID_PCM_S16LE).unwrap().id,
+            AV_CODEC_ID_PCM_S16LE
+        );
+        if let Some(x264) = find_encoder_by_name("libx264") {
+            assert_eq!(x264.id, AV_CODEC_ID_H264);
+        }
+        assert!(find_decoder(AV_CODEC_ID_NONE).is_none());
+        assert!(find_encoder_by_name("no-such-encoder").is_none());
+        assert!(find_decoder_by_name("no-such-decoder").is_none());
+    }
+
+    #[test]
+    fn test_owned_codec_context() {
+        let codec = find_decoder(AVCodecID::AV_CODEC_ID_H264).unwrap();
+        let mut ctx = OwnedCodecContext::new(Some(codec)).unwrap();
+        assert_eq!(ctx.codec_id, AVCodecID::AV_CODEC_ID_H264);
+        assert_eq!(ctx.codec_type, AVMediaType::AVMEDIA_TYPE_VIDEO);
+        ctx.set_threads(2);
+        assert_eq!(ctx.thread_count, 2);
+        drop(ctx);
+
+        let ctx = OwnedCodecContext::new(None).unwrap();
+        assert_eq!(ctx.codec_id, AVCodecID::AV_CODEC_ID_NONE);
+
+        let mut ptr = alloc_codec_context(Some(codec)).unwrap();
+        unsafe { avcodec_free_context(&mut ptr) };
+        assert!(ptr.is_null());
+    }
+
+    #[test]
+    fn test_owned_codec_context_from_stream() {
+        use crate::{
+            avformat_alloc_context, avformat_close_input, avformat_free_context,
+            avformat_new_stream, avformat_open_input,
+        };
+
+        // A YUV4MPEG2 file with a single 16x8 yuv420p frame.
+        let path = std::env::temp_dir().join("ffav-sys-test-codec-context-from-stream.y4m");
+        let mut data = b"YUV4MPEG2 W16 H8 F25:1 Ip A1:1 C420jpeg\nFRAME\n".to_vec();
+        data.resize(data.len() + 16 * 8 * 3 / 2, 0x80);
+        std::fs::write(&path, data).unwrap();
+        let url = CString::new(path.to_str().unwrap()).unwrap();
+        unsafe {
+            let mut fmt_ctx = std::ptr::null_mut();
+            let ret = avformat_open_input(
+                &mut fmt_ctx,
+                url.as_ptr(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            );
+            assert_eq!(ret, 0);
+            let st = (*fmt_ctx)
+                .streams()
+                .iter()
+                .find(|st| {
+                    st.codecpar().map(|v| v.codec_type) == Some(AVMediaType::AVMEDIA_TYPE_VIDEO)
+                })
+                .unwrap();
+            let ctx = OwnedCodecContext::from_stream(st).unwrap();
+            assert!(!ctx.codec.is_null());
+            assert_eq!((*ctx.codec).id, AVCodecID::AV_CODEC_ID_RAWVIDEO);
+            assert_eq!(ctx.codec_id, AVCodecID::AV_CODEC_ID_RAWVIDEO);
+            assert_eq!((ctx.width, ctx.height), (16, 8));
+            drop(ctx);
+            avformat_close_input(&mut fmt_ctx);
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        unsafe {
+            let fmt_ctx = avformat_alloc_context();
+            let st = avformat_new_stream(fmt_ctx, std::ptr::null());
+            (*(*st).codecpar).codec_id = AVCodecID::AV_CODEC_ID_NONE;
+            assert_eq!(
+                OwnedCodecContext::from_stream(&*st).unwrap_err(),
+                AvError(AVERROR_DECODER_NOT_FOUND)
+            );
+            avformat_free_context(fmt_ctx);
+        }
+    }
+
+    #[test]
+    fn test_owned_codec_context_configure() {
+        use crate::AVERROR_OPTION_NOT_FOUND;
+
+        let mut ctx = OwnedCodecContext::new(None).unwrap();
+        ctx.configure(&[("b", "1000000"), ("g", "50")]).unwrap();
+        assert_eq!(ctx.bit_rate, 1000000);
+        assert_eq!(ctx.gop_size, 50);
+        let err = ctx
+            .configure(&[("g", "25"), ("no-such-option", "1"), ("bad\0key", "1")])
+            .unwrap_err();
+        assert_eq!(err.key, "no-such-option");
+        assert_eq!(err.error, AvError(AVERROR_OPTION_NOT_FOUND));
+        assert!(err.to_string().contains("no-such-option"));
+        assert_eq!(AvError::from(err), AvError(AVERROR_OPTION_NOT_FOUND));
+        assert_eq!(ctx.gop_size, 25);
+
+        if let Some(codec) = find_encoder_by_name("libx264") {
+            let mut ctx = OwnedCodecContext::new(Some(codec)).unwrap();
+            ctx.configure(&[("preset", "fast"), ("crf", "23")]).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_codec_tag() {
+        use crate::{guess_output_format, AV_CODEC_TAG_AVC1, AV_CODEC_TAG_HVC1};
+        use AVCodecID::*;
+
+        let mp4 = guess_output_format(Some("mp4"), None, None).unwrap();
+        unsafe {
+            assert_eq!(
+                codec_tag_to_id(mp4.codec_tag, AV_CODEC_TAG_AVC1),
+                AV_CODEC_ID_H264
+            );
+            assert_eq!(
+                codec_tag_to_id(mp4.codec_tag, AV_CODEC_TAG_HVC1),
+                AV_CODEC_ID_HEVC
+            );
+            assert_eq!(codec_tag_to_id(mp4.codec_tag, 0), AV_CODEC_ID_NONE);
+            assert_eq!(
+                codec_id_to_tag(mp4.codec_tag, AV_CODEC_ID_H264),
+                AV_CODEC_TAG_AVC1
+            );
+        }
+        assert_eq!(mp4.codec_id_for_tag(AV_CODEC_TAG_AVC1), AV_CODEC_ID_H264);
+        assert_eq!(mp4.codec_tag_for_id(AV_CODEC_ID_H264), AV_CODEC_TAG_AVC1);
+    }
+
+    #[test]
+    fn test_pcm_codec() {
+        use AVCodecID::*;
+        use AVSampleFormat::*;
+
+        assert_eq!(
+            pcm_codec(AV_SAMPLE_FMT_S16, Some(false)),
+            AV_CODEC_ID_PCM_S16LE
+        );
+        assert_eq!(
+            pcm_codec(AV_SAMPLE_FMT_S16, Some(true)),
+            AV_CODEC_ID_PCM_S16BE
+        );
+        assert_eq!(
+            pcm_codec(AV_SAMPLE_FMT_FLT, Some(true)),
+            AV_CODEC_ID_PCM_F32BE
+        );
+        assert_eq!(pcm_codec(AV_SAMPLE_FMT_NONE, None), AV_CODEC_ID_NONE);
+        #[cfg(target_endian = "little")]
+        assert_eq!(pcm_codec(AV_SAMPLE_FMT_S32, None), AV_CODEC_ID_PCM_S32LE);
+        #[cfg(target_endian = "big")]
+        assert_eq!(pcm_codec(AV_SAMPLE_FMT_S32, None), AV_CODEC_ID_PCM_S32BE);
+    }
+
+    #[test]
+    fn test_avcodecparameters() {
+        let mut par: AVCodecParameters = unsafe { std::mem::zeroed() };
+        assert_eq!(par.extradata(), &[]);
+        par.extradata_size = 4;
+        assert_eq!(par.extradata(), &[]);
+
+        let mut extradata = [1u8, 2, 3, 4];
+        par.extradata = extradata.as_mut_ptr();
+        assert_eq!(par.extradata(), &[1, 2, 3, 4]);
+
+        par.codec_type = AVMediaType::AVMEDIA_TYPE_VIDEO;
+        par.codec_id = AVCodecID::AV_CODEC_ID_H264;
+        par.width = 1280;
+        par.height = 720;
+        assert_eq!(par.codec_type(), AVMediaType::AVMEDIA_TYPE_VIDEO);
+        assert_eq!(par.codec_id(), AVCodecID::AV_CODEC_ID_H264);
+        assert_eq!(par.dimensions(), (1280, 720));
+        par.set_codec_tag(crate::AV_CODEC_TAG_AVC1);
+        assert_eq!(par.codec_tag, crate::AV_CODEC_TAG_AVC1);
+    }
+
+    #[test]
+    fn test_avpacket_clone_ref() {
+        use crate::{av_new_packet, av_packet_unref};
+
+        unsafe {
+            let mut pkt = AVPacket::empty();
+            assert_eq!(av_new_packet(&mut pkt, 4), 0);
+            pkt.as_bytes_mut().copy_from_slice(&[1, 2, 3, 4]);
+            pkt.pts = 100;
+
+            let mut other = pkt.clone_ref().unwrap();
+            assert_eq!(other.as_bytes(), &[1, 2, 3, 4]);
+            assert_eq!(other.pts, 100);
+            assert_eq!(other.data, pkt.data);
+
+            other.make_writable().unwrap();
+            assert_ne!(other.data, pkt.data);
+            other.as_bytes_mut()[0] = 5;
+            assert_eq!(pkt.as_bytes(), &[1, 2, 3, 4]);
+
+            other.duration = 10;
+            other.rescale_ts(AVRational::new(1, 1000), AVRational::new(1, 90000));
+            assert_eq!((other.pts, other.duration), (9000, 900));
+            assert_eq!(other.dts, AV_NOPTS_VALUE);
+
+            av_packet_unref(&mut other);
+            av_packet_unref(&mut pkt);
+        }
+    }
+
+    #[test]
+    fn test_avpacket_flags() {
+        use crate::{AV_PKT_FLAG_CORRUPT, AV_PKT_FLAG_DISCARD, AV_PKT_FLAG_KEY};
+
+        let mut pkt = AVPacket::default();
+        assert!(!pkt.is_keyframe());
+        assert!(!pkt.is_corrupt());
+        assert!(!pkt.is_discard());
+
+        pkt.set_keyframe(true);
+        assert!(pkt.is_keyframe());
+        assert_eq!(pkt.flags, AV_PKT_FLAG_KEY);
+        pkt.flags |= AV_PKT_FLAG_CORRUPT;
+        assert!(pkt.is_corrupt());
+        pkt.flags |= AV_PKT_FLAG_DISCARD;
+        assert!(pkt.is_discard());
+        pkt.set_keyframe(false);
+        assert!(!pkt.is_keyframe());
+        assert_eq!(pkt.flags, AV_PKT_FLAG_CORRUPT | AV_PKT_FLAG_DISCARD);
+    }
+
+    #[test]
+    fn test_avpacket_side_data_of() {
+        use AVPacketSideDataType::*;
+
+        let mut matrix = [0u8; 36];
+        let mut gain = [1u8, 2, 3, 4, 5, 6, 7, 8];
+        let mut side_data = [
+            AVPacketSideData {
+                data: matrix.as_mut_ptr(),
+                size: matrix.len() as _,
+                type_: AV_PKT_DATA_DISPLAYMATRIX,
+            },
+            AVPacketSideData {
+                data: gain.as_mut_ptr(),
+                size: gain.len() as _,
+                type_: AV_PKT_DATA_REPLAYGAIN,
+            },
+        ];
+        let mut pkt = AVPacket::default();
+        assert!(pkt.side_data_of(AV_PKT_DATA_REPLAYGAIN).is_none());
+        pkt.side_data = side_data.as_mut_ptr();
+        pkt.side_data_elems = 2;
+        assert_eq!(
+            pkt.side_data_of(AV_PKT_DATA_DISPLAYMATRIX).unwrap().len(),
+            36
+        );
+        assert_eq!(
+            pkt.side_data_of(AV_PKT_DATA_REPLAYGAIN).unwrap(),
+            &[1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        assert!(pkt.side_data_of(AV_PKT_DATA_NEW_EXTRADATA).is_none());
+    }
 }
@@ -0,0 +1,253 @@
+use crate::{
+    av_buffer_ref, av_buffer_unref, av_hwdevice_ctx_alloc, av_hwdevice_ctx_init,
+    av_hwframe_ctx_alloc, av_hwframe_ctx_init, AVBufferRef, AVCodecContext, AVHWDeviceContext,
+    AVHWDeviceType, AVHWFramesContext, AVPixelFormat, AVVAAPIDeviceContext, AvError, VADisplay,
+    AVERROR,
+};
+use libc::ENOMEM;
+
+/// A reference counted VA-API hardware device context.
+#[derive(Debug)]
+pub struct VaapiDevice {
+    ptr: *mut AVBufferRef,
+}
+
+impl VaapiDevice {
+    /// Returns the raw `AVBufferRef` of the device context.
+    pub fn as_ptr(&self) -> *mut AVBufferRef {
+        self.ptr
+    }
+
+    /// Returns the VA-API specific part of the device context.
+    pub fn hwctx(&self) -> &AVVAAPIDeviceContext {
+        unsafe {
+            let ctx = &*((*self.ptr).data as *const AVHWDeviceContext);
+            &*(ctx.hwctx as *const AVVAAPIDeviceContext)
+        }
+    }
+}
+
+impl Drop for VaapiDevice {
+    fn drop(&mut self) {
+        unsafe { av_buffer_unref(&mut self.ptr) }
+    }
+}
+
+/// A builder to create the `VaapiDevice` from an opened `VADisplay`.
+#[derive(Debug)]
+pub struct VaapiDeviceBuilder {
+    display: VADisplay,
+    driver_quirks: u32,
+}
+
+impl VaapiDeviceBuilder {
+    /// Create a new builder with the VA display.
+    ///
+    /// # Safety
+    ///
+    /// The `display` must be a valid and initialized VA display, which must
+    /// outlive the `VaapiDevice` and every frames context created from it.
+    pub unsafe fn new(display: VADisplay) -> Self {
+        Self {
+            display,
+            driver_quirks: 0,
+        }
+    }
+
+    /// Set the `AV_VAAPI_DRIVER_QUIRK_*` flags, detected from the driver if zero.
+    pub fn driver_quirks(mut self, quirks: u32) -> Self {
+        self.driver_quirks = quirks;
+        self
+    }
+
+    /// Allocate and initialize the device context.
+    pub fn build(self) -> Result<VaapiDevice, AvError> {
+        unsafe {
+            let ptr = av_hwdevice_ctx_alloc(AVHWDeviceType::AV_HWDEVICE_TYPE_VAAPI);
+            if ptr.is_null() {
+                return Err(AvError(AVERROR(ENOMEM)));
+            }
+            let device = VaapiDevice { ptr };
+            let ctx = &mut *((*ptr).data as *mut AVHWDeviceContext);
+            let hwctx = &mut *(ctx.hwctx as *mut AVVAAPIDeviceContext);
+            hwctx.display = self.display;
+            hwctx.driver_quirks = self.driver_quirks;
+            let ret = av_hwdevice_ctx_init(ptr);
+            if ret < 0 {
+                Err(AvError(ret))
+            } else {
+                Ok(device)
+            }
+        }
+    }
+}
+
+/// A reference counted pool of VA-API surfaces.
+#[derive(Debug)]
+pub struct VaapiFrames {
+    ptr: *mut AVBufferRef,
+}
+
+impl VaapiFrames {
+    /// Returns the raw `AVBufferRef` of the frames context.
+    pub fn as_ptr(&self) -> *mut AVBufferRef {
+        self.ptr
+    }
+}
+
+impl Drop for VaapiFrames {
+    fn drop(&mut self) {
+        unsafe { av_buffer_unref(&mut self.ptr) }
+    }
+}
+
+/// A builder to create the `VaapiFrames` on a `VaapiDevice`.
+#[derive(Debug)]
+pub struct VaapiFramesBuilder {
+    sw_format: AVPixelFormat,
+    width: i32,
+    height: i32,
+    initial_pool_size: i32,
+}
+
+impl VaapiFramesBuilder {
+    /// Create a new builder for surfaces of the given size, `NV12` by default.
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            sw_format: AVPixelFormat::AV_PIX_FMT_NV12,
+            width,
+            height,
+            initial_pool_size: 0,
+        }
+    }
+
+    /// Set the pixel format of the surfaces content.
+    pub fn sw_format(mut self, fmt: AVPixelFormat) -> Self {
+        self.sw_format = fmt;
+        self
+    }
+
+    /// Set the number of surfaces to pre-allocate, required by the decoders.
+    pub fn initial_pool_size(mut self, size: i32) -> Self {
+        self.initial_pool_size = size;
+        self
+    }
+
+    /// Allocate and initialize the frames context on the device.
+    pub fn build(self, device: &VaapiDevice) -> Result<VaapiFrames, AvError> {
+        unsafe {
+            let ptr = av_hwframe_ctx_alloc(device.as_ptr());
+            if ptr.is_null() {
+                return Err(AvError(AVERROR(ENOMEM)));
+            }
+            let frames = VaapiFrames { ptr };
+            let ctx = &mut *((*ptr).data as *mut AVHWFramesContext);
+            ctx.format = AVPixelFormat::AV_PIX_FMT_VAAPI;
+            ctx.sw_format = self.sw_format;
+            ctx.width = self.width;
+            ctx.height = self.height;
+            ctx.initial_pool_size = self.initial_pool_size;
+            let ret = av_hwframe_ctx_init(ptr);
+            if ret < 0 {
+                Err(AvError(ret))
+            } else {
+                Ok(frames)
+            }
+        }
+    }
+}
+
+impl AVCodecContext {
+    /// Attach the VA-API device to the codec context, must be called before `avcodec_open2`.
+    pub fn attach_vaapi_device(&mut self, device: &VaapiDevice) -> Result<(), AvError> {
+        unsafe {
+            let ptr = av_buffer_ref(device.as_ptr());
+            if ptr.is_null() {
+                return Err(AvError(AVERROR(ENOMEM)));
+            }
+            av_buffer_unref(&mut self.hw_device_ctx);
+            self.hw_device_ctx = ptr;
+        }
+        Ok(())
+    }
+
+    /// Attach the VA-API surfaces pool to the codec context, used by the encoders.
+    pub fn attach_vaapi_frames(&mut self, frames: &VaapiFrames) -> Result<(), AvError> {
+        unsafe {
+            let ptr = av_buffer_ref(frames.as_ptr());
+            if ptr.is_null() {
+                return Err(AvError(AVERROR(ENOMEM)));
+            }
+            av_buffer_unref(&mut self.hw_frames_ctx);
+            self.hw_frames_ctx = ptr;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vaapi_frames_builder() {
+        let frames = VaapiFramesBuilder::new(1920, 1080)
+            .sw_format(AVPixelFormat::AV_PIX_FMT_NV12)
+            .initial_pool_size(20);
+        assert_eq!(frames.width, 1920);
+        assert_eq!(frames.initial_pool_size, 20);
+    }
+
+    #[test]
+    fn test_vaapi_device_attach() {
+        use crate::{av_hwdevice_ctx_create, avcodec_alloc_context3, avcodec_free_context};
+
+        // Create a device on the default DRM node to borrow its display, the
+        // test is skipped if no VA-API device is available.
+        let mut base = std::ptr::null_mut();
+        let ret = unsafe {
+            av_hwdevice_ctx_create(
+                &mut base,
+                AVHWDeviceType::AV_HWDEVICE_TYPE_VAAPI,
+                std::ptr::null(),
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        if ret < 0 {
+            return;
+        }
+        let display = unsafe {
+            let ctx = &*((*base).data as *const AVHWDeviceContext);
+            (*(ctx.hwctx as *const AVVAAPIDeviceContext)).display
+        };
+
+        // The display is owned by the `base` device which outlives `device`.
+        let device = unsafe { VaapiDeviceBuilder::new(display) }
+            .driver_quirks(0)
+            .build()
+            .unwrap();
+        assert_eq!(device.hwctx().display, display);
+        let frames = VaapiFramesBuilder::new(640, 480)
+            .initial_pool_size(4)
+            .build(&device)
+            .unwrap();
+        let frames_ctx = unsafe { &*((*frames.as_ptr()).data as *const AVHWFramesContext) };
+        assert_eq!(frames_ctx.format, AVPixelFormat::AV_PIX_FMT_VAAPI);
+        assert_eq!(frames_ctx.sw_format, AVPixelFormat::AV_PIX_FMT_NV12);
+        assert_eq!((frames_ctx.width, frames_ctx.height), (640, 480));
+
+        unsafe {
+            let mut ctx = avcodec_alloc_context3(std::ptr::null());
+            let c = &mut *ctx;
+            c.attach_vaapi_device(&device).unwrap();
+            c.attach_vaapi_frames(&frames).unwrap();
+            assert_eq!((*c.hw_device_ctx).data, (*device.as_ptr()).data);
+            assert_eq!((*c.hw_frames_ctx).data, (*frames.as_ptr()).data);
+            avcodec_free_context(&mut ctx);
+        }
+        drop(frames);
+        drop(device);
+        unsafe { av_buffer_unref(&mut base) };
+    }
+}
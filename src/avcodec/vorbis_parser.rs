@@ -0,0 +1,94 @@
+use crate::{
+    av_vorbis_parse_frame_flags, av_vorbis_parse_free, av_vorbis_parse_init, av_vorbis_parse_reset,
+    AVVorbisParseContext,
+};
+use std::convert::TryInto;
+
+/// A Vorbis parser used to determine the duration of Vorbis packets.
+#[derive(Debug)]
+pub struct VorbisParser {
+    ptr: *mut AVVorbisParseContext,
+}
+
+impl VorbisParser {
+    /// Create a new parser from the codec extradata (the three Vorbis headers).
+    ///
+    /// Returns `None` if the extradata is invalid.
+    pub fn new(extradata: &[u8]) -> Option<Self> {
+        let size = extradata.len().try_into().ok()?;
+        let ptr = unsafe { av_vorbis_parse_init(extradata.as_ptr(), size) };
+        if ptr.is_null() {
+            None
+        } else {
+            Some(Self { ptr })
+        }
+    }
+
+    /// Parse one Vorbis packet, returns the duration in samples and the flags.
+    ///
+    /// The flags is a combination of `VORBIS_FLAG_HEADER`, `VORBIS_FLAG_COMMENT`
+    /// and `VORBIS_FLAG_SETUP` when the packet is a header packet, the duration
+    /// is negative if the packet is invalid.
+    pub fn parse(&mut self, data: &[u8]) -> (i32, i32) {
+        let mut flags = 0;
+        let size = data.len().try_into().unwrap_or(i32::MAX);
+        let duration =
+            unsafe { av_vorbis_parse_frame_flags(self.ptr, data.as_ptr(), size, &mut flags) };
+        (duration, flags)
+    }
+
+    /// Reset the parser state, call it after seeking.
+    pub fn reset(&mut self) {
+        unsafe { av_vorbis_parse_reset(self.ptr) }
+    }
+}
+
+impl Drop for VorbisParser {
+    fn drop(&mut self) {
+        unsafe { av_vorbis_parse_free(&mut self.ptr) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{VORBIS_FLAG_COMMENT, VORBIS_FLAG_HEADER};
+
+    fn extradata() -> Vec<u8> {
+        // Identification header, blocksizes 256/2048.
+        let mut id = vec![1u8];
+        id.extend_from_slice(b"vorbis");
+        id.extend_from_slice(&0u32.to_le_bytes());
+        id.push(2);
+        id.extend_from_slice(&44100u32.to_le_bytes());
+        id.extend_from_slice(&[0u8; 12]);
+        id.push(0xB8);
+        id.push(1);
+        // Comment header.
+        let mut comment = vec![3u8];
+        comment.extend_from_slice(b"vorbis");
+        // Setup header with a single short-block mode and the framing bit.
+        let mut setup = vec![5u8];
+        setup.extend_from_slice(b"vorbis");
+        setup.extend_from_slice(&[0u8; 32]);
+        setup.push(1);
+
+        let mut data = vec![];
+        for header in [id, comment, setup].iter() {
+            data.extend_from_slice(&(header.len() as u16).to_be_bytes());
+            data.extend_from_slice(header);
+        }
+        data
+    }
+
+    #[test]
+    fn test_vorbis_parser() {
+        assert!(VorbisParser::new(&[]).is_none());
+
+        let mut parser = VorbisParser::new(&extradata()).unwrap();
+        assert_eq!(parser.parse(&[1]), (0, VORBIS_FLAG_HEADER));
+        assert_eq!(parser.parse(&[3]), (0, VORBIS_FLAG_COMMENT));
+        assert_eq!(parser.parse(&[0]), (128, 0));
+        parser.reset();
+    }
+}
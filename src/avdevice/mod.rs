@@ -0,0 +1,71 @@
+use crate::{
+    avdevice_free_list_devices, avdevice_list_input_sources, avdevice_list_output_sinks,
+    avdevice_register_all, AVDeviceInfoList, AvError, AVERROR,
+};
+use libc::EINVAL;
+use std::ffi::{CStr, CString};
+
+fn list_devices(name: &str, output: bool) -> Result<Vec<String>, AvError> {
+    let name = CString::new(name).map_err(|_| AvError(AVERROR(EINVAL)))?;
+    unsafe {
+        avdevice_register_all();
+        let mut list: *mut AVDeviceInfoList = std::ptr::null_mut();
+        let ret = if output {
+            avdevice_list_output_sinks(
+                std::ptr::null_mut(),
+                name.as_ptr(),
+                std::ptr::null_mut(),
+                &mut list,
+            )
+        } else {
+            avdevice_list_input_sources(
+                std::ptr::null_mut(),
+                name.as_ptr(),
+                std::ptr::null_mut(),
+                &mut list,
+            )
+        };
+        if ret < 0 {
+            avdevice_free_list_devices(&mut list);
+            return Err(AvError(ret));
+        }
+        let mut names = vec![];
+        if !list.is_null() && !(*list).devices.is_null() && (*list).nb_devices > 0 {
+            let devices = std::slice::from_raw_parts((*list).devices, (*list).nb_devices as usize);
+            for &device in devices {
+                if !device.is_null() && !(*device).device_name.is_null() {
+                    names.push(
+                        CStr::from_ptr((*device).device_name)
+                            .to_string_lossy()
+                            .into_owned(),
+                    );
+                }
+            }
+        }
+        avdevice_free_list_devices(&mut list);
+        Ok(names)
+    }
+}
+
+/// List the available sources of the input device, such as `"alsa"` or `"v4l2"`.
+pub fn list_input_sources(name: &str) -> Result<Vec<String>, AvError> {
+    list_devices(name, false)
+}
+
+/// List the available sinks of the output device, such as `"alsa"` or `"sdl"`.
+pub fn list_output_sinks(name: &str) -> Result<Vec<String>, AvError> {
+    list_devices(name, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_output_sinks() {
+        assert_eq!(list_output_sinks("a\0b"), Err(AvError(AVERROR(EINVAL))));
+        // The device may be not available, just make sure nothing goes wrong.
+        let _ = list_output_sinks("alsa");
+        let _ = list_output_sinks("nonexistent");
+    }
+}
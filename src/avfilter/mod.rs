@@ -0,0 +1,232 @@
+use crate::{
+    av_buffersink_get_channels, av_buffersink_get_format, av_buffersink_get_h,
+    av_buffersink_get_sample_rate, av_buffersink_get_time_base, av_buffersink_get_w,
+    av_buffersrc_close, AVFilterContext, AVPixelFormat, AVRational, AVSampleFormat, AvError,
+};
+
+/// Signal the end of stream at `pts` to the buffer source `ctx`, the filters
+/// buffering the data, such as `ebur128` or `loudnorm`, are finalized and
+/// the remaining frames are drained to the sinks.
+///
+/// # Safety
+///
+/// The `ctx` must be a valid `buffer` or `abuffer` filter context.
+pub unsafe fn buffersrc_close(ctx: *mut AVFilterContext, pts: i64) -> Result<(), AvError> {
+    let ret = av_buffersrc_close(ctx, pts, 0);
+    if ret < 0 {
+        Err(AvError(ret))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the negotiated format of the buffer sink `ctx`, a `AVPixelFormat` for
+/// video or a `AVSampleFormat` for audio.
+///
+/// # Safety
+///
+/// The `ctx` must be a valid `buffersink` or `abuffersink` filter context.
+pub unsafe fn buffersink_get_format(ctx: *const AVFilterContext) -> i32 {
+    av_buffersink_get_format(ctx)
+}
+
+/// Returns the negotiated pixel format of the video buffer sink `ctx`, `None`
+/// if the format is not a known pixel format.
+///
+/// # Safety
+///
+/// The `ctx` must be a valid `buffersink` or `abuffersink` filter context.
+pub unsafe fn buffersink_get_pix_fmt(ctx: *const AVFilterContext) -> Option<AVPixelFormat> {
+    AVPixelFormat::from_raw(av_buffersink_get_format(ctx))
+}
+
+/// Returns the negotiated sample format of the audio buffer sink `ctx`, `None`
+/// if the format is not a known sample format.
+///
+/// # Safety
+///
+/// The `ctx` must be a valid `buffersink` or `abuffersink` filter context.
+pub unsafe fn buffersink_get_sample_fmt(ctx: *const AVFilterContext) -> Option<AVSampleFormat> {
+    AVSampleFormat::from_raw(av_buffersink_get_format(ctx))
+}
+
+/// Returns the negotiated width of the video buffer sink `ctx`.
+///
+/// # Safety
+///
+/// The `ctx` must be a valid `buffersink` or `abuffersink` filter context.
+pub unsafe fn buffersink_get_w(ctx: *const AVFilterContext) -> i32 {
+    av_buffersink_get_w(ctx)
+}
+
+/// Returns the negotiated height of the video buffer sink `ctx`.
+///
+/// # Safety
+///
+/// The `ctx` must be a valid `buffersink` or `abuffersink` filter context.
+pub unsafe fn buffersink_get_h(ctx: *const AVFilterContext) -> i32 {
+    av_buffersink_get_h(ctx)
+}
+
+/// Returns the negotiated sample rate of the audio buffer sink `ctx`.
+///
+/// # Safety
+///
+/// The `ctx` must be a valid `buffersink` or `abuffersink` filter context.
+pub unsafe fn buffersink_get_sample_rate(ctx: *const AVFilterContext) -> i32 {
+    av_buffersink_get_sample_rate(ctx)
+}
+
+/// Returns the negotiated number of channels of the audio buffer sink `ctx`.
+///
+/// # Safety
+///
+/// The `ctx` must be a valid `buffersink` or `abuffersink` filter context.
+pub unsafe fn buffersink_get_channels(ctx: *const AVFilterContext) -> i32 {
+    av_buffersink_get_channels(ctx)
+}
+
+/// Returns the time base of the buffer sink `ctx`.
+///
+/// # Safety
+///
+/// The `ctx` must be a valid `buffersink` or `abuffersink` filter context.
+pub unsafe fn buffersink_get_time_base(ctx: *const AVFilterContext) -> AVRational {
+    av_buffersink_get_time_base(ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        av_buffersink_get_frame, av_buffersrc_add_frame, av_frame_alloc, av_frame_free,
+        av_frame_get_buffer, av_frame_unref, avfilter_get_by_name, avfilter_graph_alloc,
+        avfilter_graph_config, avfilter_graph_create_filter, avfilter_graph_free, avfilter_link,
+        AVFilterGraph, AVERROR, AVERROR_EOF, AV_CH_LAYOUT_STEREO,
+    };
+    use libc::EAGAIN;
+    use std::ffi::CString;
+
+    unsafe fn create_filter(
+        graph: *mut AVFilterGraph,
+        filter: &str,
+        name: &str,
+        args: Option<&str>,
+    ) -> *mut AVFilterContext {
+        let filter = CString::new(filter).unwrap();
+        let name = CString::new(name).unwrap();
+        let args = args.map(|v| CString::new(v).unwrap());
+        let mut ctx = std::ptr::null_mut();
+        let ret = avfilter_graph_create_filter(
+            &mut ctx,
+            avfilter_get_by_name(filter.as_ptr()),
+            name.as_ptr(),
+            args.as_ref().map_or(std::ptr::null(), |v| v.as_ptr()),
+            std::ptr::null_mut(),
+            graph,
+        );
+        assert!(ret >= 0);
+        ctx
+    }
+
+    #[test]
+    fn test_buffersrc_close() {
+        unsafe {
+            let mut graph = avfilter_graph_alloc();
+            let src = create_filter(
+                graph,
+                "abuffer",
+                "in",
+                Some("sample_rate=48000:sample_fmt=dbl:channel_layout=stereo:time_base=1/48000"),
+            );
+            let ebur128 = create_filter(graph, "ebur128", "ebur128", None);
+            let sink = create_filter(graph, "abuffersink", "out", None);
+            assert_eq!(avfilter_link(src, 0, ebur128, 0), 0);
+            assert_eq!(avfilter_link(ebur128, 0, sink, 0), 0);
+            assert!(avfilter_graph_config(graph, std::ptr::null_mut()) >= 0);
+
+            let mut frame = av_frame_alloc();
+            let mut nb_samples_in = 0;
+            let mut nb_samples_out = 0;
+            for i in 0..10 {
+                (*frame).format = AVSampleFormat::AV_SAMPLE_FMT_DBL as i32;
+                (*frame).channel_layout = AV_CH_LAYOUT_STEREO;
+                (*frame).channels = 2;
+                (*frame).sample_rate = 48000;
+                (*frame).nb_samples = 1024;
+                (*frame).pts = i * 1024;
+                assert_eq!(av_frame_get_buffer(frame, 0), 0);
+                std::ptr::write_bytes((*frame).data[0], 0, 1024 * 2 * 8);
+                assert_eq!(av_buffersrc_add_frame(src, frame), 0);
+                nb_samples_in += 1024;
+            }
+            let drain = |frame: *mut _, nb_samples: &mut i32| loop {
+                let ret = av_buffersink_get_frame(sink, frame);
+                if ret < 0 {
+                    break ret;
+                }
+                *nb_samples += (*frame).nb_samples;
+                av_frame_unref(frame);
+            };
+            assert_eq!(drain(frame, &mut nb_samples_out), AVERROR(EAGAIN));
+
+            buffersrc_close(src, 10 * 1024).unwrap();
+            assert_eq!(drain(frame, &mut nb_samples_out), AVERROR_EOF);
+            assert_eq!(nb_samples_out, nb_samples_in);
+
+            av_frame_free(&mut frame);
+            avfilter_graph_free(&mut graph);
+        }
+    }
+
+    #[test]
+    fn test_buffersink_getters() {
+        unsafe {
+            let mut graph = avfilter_graph_alloc();
+            let src = create_filter(
+                graph,
+                "buffer",
+                "in",
+                Some("video_size=16x16:pix_fmt=yuv420p:time_base=1/25:pixel_aspect=1/1"),
+            );
+            let format = create_filter(graph, "format", "format", Some("pix_fmts=rgb24"));
+            let sink = create_filter(graph, "buffersink", "out", None);
+            assert_eq!(avfilter_link(src, 0, format, 0), 0);
+            assert_eq!(avfilter_link(format, 0, sink, 0), 0);
+            assert!(avfilter_graph_config(graph, std::ptr::null_mut()) >= 0);
+
+            assert_eq!(
+                buffersink_get_format(sink),
+                AVPixelFormat::AV_PIX_FMT_RGB24 as i32
+            );
+            assert_eq!(
+                buffersink_get_pix_fmt(sink),
+                Some(AVPixelFormat::AV_PIX_FMT_RGB24)
+            );
+            assert_eq!((buffersink_get_w(sink), buffersink_get_h(sink)), (16, 16));
+            assert_eq!(
+                buffersink_get_time_base(sink),
+                AVRational { num: 1, den: 25 }
+            );
+            avfilter_graph_free(&mut graph);
+
+            let mut graph = avfilter_graph_alloc();
+            let src = create_filter(
+                graph,
+                "abuffer",
+                "in",
+                Some("sample_rate=44100:sample_fmt=s16:channel_layout=stereo:time_base=1/44100"),
+            );
+            let sink = create_filter(graph, "abuffersink", "out", None);
+            assert_eq!(avfilter_link(src, 0, sink, 0), 0);
+            assert!(avfilter_graph_config(graph, std::ptr::null_mut()) >= 0);
+            assert_eq!(
+                buffersink_get_sample_fmt(sink),
+                Some(AVSampleFormat::AV_SAMPLE_FMT_S16)
+            );
+            assert_eq!(buffersink_get_sample_rate(sink), 44100);
+            assert_eq!(buffersink_get_channels(sink), 2);
+            avfilter_graph_free(&mut graph);
+        }
+    }
+}
@@ -0,0 +1,628 @@
+use crate::{
+    av_freep, av_malloc, avio_alloc_context, avio_context_free, avio_enum_protocols, avio_open2,
+    avio_r8, avio_rb32, avio_read, avio_rl32, avio_seek, avio_size, avio_w8, avio_wb32, avio_wl32,
+    avio_write, AVDictionary, AVIOContext, AVIOInterruptCB, AvError, AVERROR, AVERROR_EOF,
+    AVSEEK_FORCE, AVSEEK_SIZE,
+};
+use libc::{c_int, c_void, EINVAL, EIO, ENOMEM, SEEK_CUR, SEEK_END, SEEK_SET};
+use std::ffi::CStr;
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Create and initialize an I/O context for accessing the resource of `url`.
+///
+/// On return, the `options` is filled with the options which were not found.
+/// The returned context must be closed with `avio_closep()`.
+pub fn avio_open_with_options(
+    url: &CStr,
+    flags: i32,
+    options: Option<&mut *mut AVDictionary>,
+) -> Result<*mut AVIOContext, AvError> {
+    let mut ctx = std::ptr::null_mut();
+    let ret = unsafe {
+        avio_open2(
+            &mut ctx,
+            url.as_ptr(),
+            flags,
+            std::ptr::null(),
+            options.map_or(std::ptr::null_mut(), |v| v),
+        )
+    };
+    if ret < 0 {
+        Err(AvError(ret))
+    } else {
+        Ok(ctx)
+    }
+}
+
+/// Returns the names of all available protocols for the `output` or input.
+pub fn protocols(output: bool) -> impl Iterator<Item = String> {
+    let mut opaque = std::ptr::null_mut();
+    std::iter::from_fn(move || unsafe {
+        let name = avio_enum_protocols(&mut opaque, output as c_int);
+        if name.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(name).to_string_lossy().into_owned())
+        }
+    })
+}
+
+type InterruptFn = Box<dyn Fn() -> bool + Send>;
+
+unsafe extern "C" fn interrupt_trampoline(opaque: *mut c_void) -> c_int {
+    let cb = &*(opaque as *const InterruptFn);
+    cb() as c_int
+}
+
+/// The Rust closure as an `AVIOInterruptCB`, the blocking operations are
+/// aborted when the closure returns `true`.
+pub struct InterruptCallback {
+    cb: Box<InterruptFn>,
+}
+
+impl InterruptCallback {
+    /// Create a new callback with the closure.
+    pub fn new<F>(cb: F) -> Self
+    where
+        F: Fn() -> bool + Send + 'static,
+    {
+        Self {
+            cb: Box::new(Box::new(cb)),
+        }
+    }
+
+    /// Returns the raw callback, such as for `AVFormatContext.interrupt_callback`.
+    ///
+    /// The raw callback is only valid while `self` is alive.
+    pub fn as_raw(&self) -> AVIOInterruptCB {
+        AVIOInterruptCB {
+            callback: Some(interrupt_trampoline),
+            opaque: &*self.cb as *const InterruptFn as *mut c_void,
+        }
+    }
+}
+
+impl std::fmt::Debug for InterruptCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InterruptCallback").finish()
+    }
+}
+
+trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek> ReadSeek for T {}
+
+type BoxedReader = Box<dyn ReadSeek>;
+
+fn io_error_code(err: &io::Error) -> c_int {
+    AVERROR(err.raw_os_error().unwrap_or(EIO))
+}
+
+unsafe extern "C" fn reader_read_packet(
+    opaque: *mut c_void,
+    buf: *mut u8,
+    buf_size: c_int,
+) -> c_int {
+    let reader = &mut *(opaque as *mut BoxedReader);
+    let buf = std::slice::from_raw_parts_mut(buf, buf_size.max(0) as usize);
+    loop {
+        match reader.read(buf) {
+            Ok(0) => return AVERROR_EOF,
+            Ok(n) => return n as c_int,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(ref e) => return io_error_code(e),
+        }
+    }
+}
+
+unsafe extern "C" fn reader_seek(opaque: *mut c_void, offset: i64, whence: c_int) -> i64 {
+    let reader = &mut *(opaque as *mut BoxedReader);
+    let pos = match whence & !AVSEEK_FORCE {
+        AVSEEK_SIZE => {
+            // Report the size without moving the position.
+            let size = reader.seek(SeekFrom::Current(0)).and_then(|pos| {
+                let size = reader.seek(SeekFrom::End(0))?;
+                reader.seek(SeekFrom::Start(pos))?;
+                Ok(size)
+            });
+            return size.map_or_else(|e| io_error_code(&e) as i64, |v| v as i64);
+        }
+        SEEK_SET if offset >= 0 => SeekFrom::Start(offset as u64),
+        SEEK_CUR => SeekFrom::Current(offset),
+        SEEK_END => SeekFrom::End(offset),
+        _ => return AVERROR(EINVAL) as i64,
+    };
+    reader
+        .seek(pos)
+        .map_or_else(|e| io_error_code(&e) as i64, |v| v as i64)
+}
+
+/// The I/O context which reads from a Rust reader, such as for demuxing from
+/// the memory with `AVFormatContext.pb`.
+///
+/// The reader and the I/O buffer are freed on drop, so the context must
+/// outlive the format context which uses it.
+pub struct AvioContext {
+    ptr: *mut AVIOContext,
+    opaque: *mut BoxedReader,
+}
+
+impl AvioContext {
+    /// Create a new I/O context reading from the `reader` through a buffer of
+    /// `buffer_size` bytes.
+    pub fn from_reader<R>(reader: R, buffer_size: usize) -> Result<Self, AvError>
+    where
+        R: Read + Seek + 'static,
+    {
+        if buffer_size == 0 || buffer_size > c_int::MAX as usize {
+            return Err(AvError(AVERROR(EINVAL)));
+        }
+        unsafe {
+            let buffer = av_malloc(buffer_size) as *mut u8;
+            if buffer.is_null() {
+                return Err(AvError(AVERROR(ENOMEM)));
+            }
+            let reader: BoxedReader = Box::new(reader);
+            let opaque = Box::into_raw(Box::new(reader));
+            let ptr = avio_alloc_context(
+                buffer,
+                buffer_size as c_int,
+                0,
+                opaque as *mut c_void,
+                Some(reader_read_packet),
+                None,
+                Some(reader_seek),
+            );
+            if ptr.is_null() {
+                let mut buffer = buffer;
+                av_freep(&mut buffer as *mut *mut u8 as *mut c_void);
+                drop(Box::from_raw(opaque));
+                return Err(AvError(AVERROR(ENOMEM)));
+            }
+            Ok(Self { ptr, opaque })
+        }
+    }
+
+    /// Returns the raw pointer of the I/O context.
+    pub fn as_ptr(&self) -> *const AVIOContext {
+        self.ptr
+    }
+
+    /// Returns the mutable raw pointer of the I/O context, such as for
+    /// `AVFormatContext.pb`.
+    pub fn as_mut_ptr(&mut self) -> *mut AVIOContext {
+        self.ptr
+    }
+}
+
+impl std::ops::Deref for AvioContext {
+    type Target = AVIOContext;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.ptr }
+    }
+}
+
+impl std::ops::DerefMut for AvioContext {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.ptr }
+    }
+}
+
+impl Drop for AvioContext {
+    fn drop(&mut self) {
+        unsafe {
+            // The internal buffer may be reallocated, free the current one.
+            av_freep(&mut (*self.ptr).buffer as *mut *mut u8 as *mut c_void);
+            avio_context_free(&mut self.ptr);
+            drop(Box::from_raw(self.opaque));
+        }
+    }
+}
+
+impl std::fmt::Debug for AvioContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AvioContext")
+            .field("ptr", &self.ptr)
+            .finish()
+    }
+}
+
+impl AVIOContext {
+    /// Returns the size of the resource.
+    pub fn size(&mut self) -> Result<i64, AvError> {
+        let ret = unsafe { avio_size(self) };
+        if ret < 0 {
+            Err(AvError(ret as i32))
+        } else {
+            Ok(ret)
+        }
+    }
+
+    /// Returns the current position.
+    pub fn tell(&mut self) -> i64 {
+        // Seeking by 0 from the current position only reports the position.
+        unsafe { avio_seek(self, 0, SEEK_CUR) }
+    }
+
+    /// Seek to the `offset` from the `whence` (`SEEK_SET` or `SEEK_CUR`),
+    /// returns the new position.
+    pub fn seek(&mut self, offset: i64, whence: i32) -> Result<i64, AvError> {
+        let ret = unsafe { avio_seek(self, offset, whence) };
+        if ret < 0 {
+            Err(AvError(ret as i32))
+        } else {
+            Ok(ret)
+        }
+    }
+
+    /// Returns `true` if the end of the stream or an error was reached.
+    #[inline]
+    pub fn eof_reached(&self) -> bool {
+        self.eof_reached != 0
+    }
+
+    /// Returns the error code if an error occurred, 0 otherwise.
+    #[inline]
+    pub fn error(&self) -> i32 {
+        self.error
+    }
+
+    /// Returns the error if occurred, to distinguish the errors from the clean
+    /// end of the stream after a failed read.
+    pub fn check_error(&self) -> Result<(), AvError> {
+        if self.error < 0 {
+            Err(AvError(self.error))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Read up to `buf.len()` bytes into the `buf`, returns the number of
+    /// bytes read, 0 at the end of the stream.
+    pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, AvError> {
+        let size = buf.len().min(c_int::MAX as usize) as c_int;
+        let ret = unsafe { avio_read(self, buf.as_mut_ptr(), size) };
+        if ret == AVERROR_EOF {
+            Ok(0)
+        } else if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(ret as usize)
+        }
+    }
+
+    /// Write all bytes of the `buf`.
+    ///
+    /// The write errors are reported by `check_error()`.
+    pub fn write(&mut self, buf: &[u8]) {
+        for chunk in buf.chunks(c_int::MAX as usize) {
+            unsafe { avio_write(self, chunk.as_ptr(), chunk.len() as c_int) };
+        }
+    }
+
+    /// Read a byte, returns 0 at the end of the stream.
+    #[inline]
+    pub fn read_byte(&mut self) -> u8 {
+        unsafe { avio_r8(self) as u8 }
+    }
+
+    /// Read a little-endian 32-bit integer.
+    #[inline]
+    pub fn read_le32(&mut self) -> u32 {
+        unsafe { avio_rl32(self) }
+    }
+
+    /// Read a big-endian 32-bit integer.
+    #[inline]
+    pub fn read_be32(&mut self) -> u32 {
+        unsafe { avio_rb32(self) }
+    }
+
+    /// Write a byte.
+    #[inline]
+    pub fn write_byte(&mut self, v: u8) {
+        unsafe { avio_w8(self, v as i32) }
+    }
+
+    /// Write a little-endian 32-bit integer.
+    #[inline]
+    pub fn write_le32(&mut self, v: u32) {
+        unsafe { avio_wl32(self, v) }
+    }
+
+    /// Write a big-endian 32-bit integer.
+    #[inline]
+    pub fn write_be32(&mut self, v: u32) {
+        unsafe { avio_wb32(self, v) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{av_free, avio_close_dyn_buf, avio_open_dyn_buf, AVIOContext};
+
+    struct Memory {
+        data: Vec<u8>,
+        pos: usize,
+    }
+
+    unsafe extern "C" fn read_packet(opaque: *mut c_void, buf: *mut u8, buf_size: c_int) -> c_int {
+        let mem = &mut *(opaque as *mut Memory);
+        let size = (buf_size as usize).min(mem.data.len() - mem.pos);
+        if size == 0 {
+            return AVERROR_EOF;
+        }
+        std::ptr::copy_nonoverlapping(mem.data.as_ptr().add(mem.pos), buf, size);
+        mem.pos += size;
+        size as c_int
+    }
+
+    unsafe extern "C" fn seek(opaque: *mut c_void, offset: i64, whence: c_int) -> i64 {
+        let mem = &mut *(opaque as *mut Memory);
+        let pos = match whence & !AVSEEK_FORCE {
+            AVSEEK_SIZE => return mem.data.len() as i64,
+            SEEK_SET => offset,
+            SEEK_CUR => mem.pos as i64 + offset,
+            SEEK_END => mem.data.len() as i64 + offset,
+            _ => return -1,
+        };
+        if pos < 0 || pos > mem.data.len() as i64 {
+            return -1;
+        }
+        mem.pos = pos as usize;
+        pos
+    }
+
+    unsafe fn open_memory(mem: &mut Memory) -> *mut AVIOContext {
+        let buffer = av_malloc(4096) as *mut u8;
+        let ctx = avio_alloc_context(
+            buffer,
+            4096,
+            0,
+            mem as *mut Memory as *mut c_void,
+            Some(read_packet),
+            None,
+            Some(seek),
+        );
+        assert!(!ctx.is_null());
+        ctx
+    }
+
+    unsafe fn close_memory(ctx: &mut *mut AVIOContext) {
+        av_freep(&mut (**ctx).buffer as *mut *mut u8 as *mut c_void);
+        avio_context_free(ctx);
+    }
+
+    #[test]
+    fn test_avio_size_tell_seek() {
+        let mut mem = Memory {
+            data: (0..10000).map(|v| v as u8).collect(),
+            pos: 0,
+        };
+        unsafe {
+            let mut ctx = open_memory(&mut mem);
+            let pb = &mut *ctx;
+            assert_eq!(pb.size(), Ok(10000));
+            assert_eq!(pb.tell(), 0);
+
+            let mut buf = [0u8; 100];
+            assert_eq!(avio_read(pb, buf.as_mut_ptr(), 100), 100);
+            assert_eq!(pb.tell(), 100);
+            assert_eq!(buf[99], 99);
+
+            assert_eq!(pb.seek(5000, SEEK_SET), Ok(5000));
+            assert_eq!(pb.tell(), 5000);
+            assert_eq!(pb.seek(-1000, SEEK_CUR), Ok(4000));
+            assert_eq!(avio_read(pb, buf.as_mut_ptr(), 1), 1);
+            assert_eq!(buf[0], (4000 % 256) as u8);
+            assert!(pb.seek(0, SEEK_END).is_err());
+            assert_eq!(pb.size(), Ok(10000));
+
+            close_memory(&mut ctx);
+        }
+    }
+
+    #[test]
+    fn test_avio_read_write() {
+        unsafe {
+            let mut ctx = std::ptr::null_mut();
+            assert_eq!(avio_open_dyn_buf(&mut ctx), 0);
+            let pb = &mut *ctx;
+            pb.write_byte(0xab);
+            pb.write_le32(0x1234_5678);
+            pb.write_be32(0x1234_5678);
+            let mut buf = std::ptr::null_mut();
+            let size = avio_close_dyn_buf(ctx, &mut buf);
+            let data = std::slice::from_raw_parts(buf, size as usize).to_vec();
+            av_free(buf as *mut c_void);
+            assert_eq!(data, [0xab, 0x78, 0x56, 0x34, 0x12, 0x12, 0x34, 0x56, 0x78]);
+
+            let mut mem = Memory { data, pos: 0 };
+            let mut ctx = open_memory(&mut mem);
+            let pb = &mut *ctx;
+            assert_eq!(pb.read_byte(), 0xab);
+            assert_eq!(pb.read_le32(), 0x1234_5678);
+            assert_eq!(pb.read_be32(), 0x1234_5678);
+            assert_eq!(pb.read_byte(), 0);
+            close_memory(&mut ctx);
+        }
+    }
+
+    #[test]
+    fn test_avio_open_with_options() {
+        use crate::{av_dict_free, av_dict_set, avio_closep, avio_write, AVIO_FLAG_WRITE};
+        use std::ffi::CString;
+
+        let path = std::env::temp_dir().join("ffav-sys-test-avio-open.bin");
+        let url = CString::new(path.to_str().unwrap()).unwrap();
+        unsafe {
+            let mut options = std::ptr::null_mut();
+            av_dict_set(
+                &mut options,
+                b"no-such-option\0".as_ptr() as _,
+                b"1\0".as_ptr() as _,
+                0,
+            );
+            let mut pb = avio_open_with_options(&url, AVIO_FLAG_WRITE, Some(&mut options)).unwrap();
+            assert_eq!((*options).to_hash_map().len(), 1);
+            av_dict_free(&mut options);
+            avio_write(pb, b"hello".as_ptr(), 5);
+            avio_closep(&mut pb);
+        }
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello");
+        std::fs::remove_file(&path).unwrap();
+
+        let url = CString::new("/no/such/dir/file.bin").unwrap();
+        assert!(avio_open_with_options(&url, crate::AVIO_FLAG_READ, None).is_err());
+    }
+
+    #[test]
+    fn test_interrupt_callback() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let abort = Arc::new(AtomicBool::new(false));
+        let cb = InterruptCallback::new({
+            let abort = abort.clone();
+            move || abort.load(Ordering::SeqCst)
+        });
+        let raw = cb.as_raw();
+        let callback = raw.callback.unwrap();
+        assert_eq!(unsafe { callback(raw.opaque) }, 0);
+        abort.store(true, Ordering::SeqCst);
+        assert_eq!(unsafe { callback(raw.opaque) }, 1);
+    }
+
+    #[test]
+    fn test_avio_eof_and_error() {
+        use crate::AVERROR;
+        use libc::EIO;
+
+        let mut mem = Memory {
+            data: vec![0u8; 100],
+            pos: 0,
+        };
+        unsafe {
+            let mut ctx = open_memory(&mut mem);
+            let pb = &mut *ctx;
+            let mut buf = [0u8; 200];
+            assert_eq!(avio_read(pb, buf.as_mut_ptr(), 100), 100);
+            assert!(!pb.eof_reached());
+            assert_eq!(avio_read(pb, buf.as_mut_ptr(), 200), AVERROR_EOF);
+            assert!(pb.eof_reached());
+            assert_eq!(pb.error(), 0);
+            assert_eq!(pb.check_error(), Ok(()));
+
+            pb.error = AVERROR(EIO);
+            assert_eq!(pb.check_error(), Err(AvError(AVERROR(EIO))));
+            close_memory(&mut ctx);
+        }
+    }
+
+    #[test]
+    fn test_avio_read_write_buf() {
+        unsafe {
+            let mut ctx = std::ptr::null_mut();
+            assert_eq!(avio_open_dyn_buf(&mut ctx), 0);
+            let pb = &mut *ctx;
+            pb.write(b"hello ");
+            pb.write(b"world");
+            pb.write(&[]);
+            let mut buf = std::ptr::null_mut();
+            let size = avio_close_dyn_buf(ctx, &mut buf);
+            let data = std::slice::from_raw_parts(buf, size as usize).to_vec();
+            av_free(buf as *mut c_void);
+            assert_eq!(data, b"hello world");
+
+            let mut mem = Memory { data, pos: 0 };
+            let mut ctx = open_memory(&mut mem);
+            let pb = &mut *ctx;
+            let mut buf = [0u8; 5];
+            assert_eq!(pb.read(&mut buf), Ok(5));
+            assert_eq!(&buf, b"hello");
+            assert_eq!(pb.tell(), 5);
+            assert_eq!(pb.seek(6, SEEK_SET), Ok(6));
+            let mut buf = [0u8; 16];
+            assert_eq!(pb.read(&mut buf), Ok(5));
+            assert_eq!(&buf[..5], b"world");
+            assert_eq!(pb.read(&mut buf), Ok(0));
+            assert!(pb.eof_reached());
+            close_memory(&mut ctx);
+        }
+    }
+
+    fn wav_file(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+        let data_size = samples.len() as u32 * 2;
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_size.to_le_bytes());
+        for v in samples {
+            wav.extend_from_slice(&v.to_le_bytes());
+        }
+        wav
+    }
+
+    #[test]
+    fn test_avio_context_from_reader() {
+        use crate::{
+            av_packet_unref, av_read_frame, avformat_alloc_context, avformat_close_input,
+            avformat_find_stream_info, avformat_open_input, AVCodecID, AVPacket,
+        };
+        use std::io::Cursor;
+
+        let samples: Vec<i16> = (0..8000).map(|v| (v % 100) as i16).collect();
+        let wav = wav_file(&samples, 8000);
+        assert!(AvioContext::from_reader(Cursor::new(wav.clone()), 0).is_err());
+
+        let mut avio = AvioContext::from_reader(Cursor::new(wav), 1024).unwrap();
+        assert_eq!(avio.size(), Ok(16044));
+        assert_eq!(avio.tell(), 0);
+        unsafe {
+            let mut ctx = avformat_alloc_context();
+            (*ctx).pb = avio.as_mut_ptr();
+            let ret = avformat_open_input(
+                &mut ctx,
+                std::ptr::null(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            );
+            assert_eq!(ret, 0);
+            assert!(avformat_find_stream_info(ctx, std::ptr::null_mut()) >= 0);
+            let streams = (*ctx).streams();
+            assert_eq!(streams.len(), 1);
+            let par = streams[0].codecpar().unwrap();
+            assert_eq!(par.codec_id, AVCodecID::AV_CODEC_ID_PCM_S16LE);
+            assert_eq!(par.sample_rate, 8000);
+            assert_eq!(par.channels, 1);
+
+            let mut pkt = AVPacket::default();
+            let mut total = 0;
+            while av_read_frame(ctx, &mut pkt) >= 0 {
+                total += pkt.size as usize;
+                av_packet_unref(&mut pkt);
+            }
+            assert_eq!(total, samples.len() * 2);
+            avformat_close_input(&mut ctx);
+        }
+        drop(avio);
+    }
+
+    #[test]
+    fn test_protocols() {
+        assert!(protocols(false).any(|v| v == "file"));
+        assert!(protocols(true).any(|v| v == "file"));
+        assert!(!protocols(false).any(|v| v.is_empty()));
+    }
+}
@@ -1,8 +1,118 @@
 use crate::{
-    AVChapter, AVCodecContext, AVCodecParameters, AVDictionary, AVFormatContext, AVIOContext,
-    AVPacketSideData, AVProgram, AVStream,
+    AVChapter, AVCodecContext, AVCodecID, AVCodecParameters, AVDictionary, AVFormatContext,
+    AVFrame, AVIOContext, AVInputFormat, AVMediaType, AVOutputFormat, AVPacketSideData,
+    AVPacketSideDataType, AVProgram, AVRational, AVStream, AvError, AVERROR, AVERROR_UNKNOWN,
+    AV_NOPTS_VALUE, AV_TIME_BASE,
 };
+use libc::EINVAL;
+use std::borrow::Cow;
 use std::convert::TryInto;
+use std::ffi::{CStr, CString};
+use std::fmt::Write;
+
+mod avio;
+pub use self::avio::*;
+
+mod output;
+pub use self::output::*;
+
+mod probe;
+pub use self::probe::*;
+
+/// Return the output format in the list of registered output formats which best
+/// matches the provided parameters.
+///
+/// The `short_name` is checked first, then the extension of the `filename` and
+/// the `mime` type. Returns `None` if no format matched.
+pub fn guess_output_format(
+    short_name: Option<&str>,
+    filename: Option<&str>,
+    mime: Option<&str>,
+) -> Option<&'static AVOutputFormat> {
+    let short_name = short_name.map(CString::new).transpose().ok()?;
+    let filename = filename.map(CString::new).transpose().ok()?;
+    let mime = mime.map(CString::new).transpose().ok()?;
+    unsafe {
+        crate::av_guess_format(
+            short_name.as_ref().map_or(std::ptr::null(), |v| v.as_ptr()),
+            filename.as_ref().map_or(std::ptr::null(), |v| v.as_ptr()),
+            mime.as_ref().map_or(std::ptr::null(), |v| v.as_ptr()),
+        )
+        .as_ref()
+    }
+}
+
+/// Returns an iterator over all registered muxers.
+pub fn muxers() -> impl Iterator<Item = &'static AVOutputFormat> {
+    let mut opaque = std::ptr::null_mut();
+    std::iter::from_fn(move || unsafe { crate::av_muxer_iterate(&mut opaque).as_ref() })
+}
+
+/// Returns an iterator over all registered demuxers.
+pub fn demuxers() -> impl Iterator<Item = &'static AVInputFormat> {
+    let mut opaque = std::ptr::null_mut();
+    std::iter::from_fn(move || unsafe { crate::av_demuxer_iterate(&mut opaque).as_ref() })
+}
+
+/// Expand the frame `number` in the `template`, such as `"frame-%04d.png"`.
+///
+/// The `template` must contain exactly one `%d` pattern, optionally with a
+/// zero padded width.
+pub fn get_frame_filename(template: &str, number: i32) -> Result<String, AvError> {
+    let template = CString::new(template).map_err(|_| AvError(AVERROR(EINVAL)))?;
+    let mut buf = vec![0u8; 1024];
+    let ret = unsafe {
+        crate::av_get_frame_filename2(
+            buf.as_mut_ptr() as *mut _,
+            buf.len() as i32,
+            template.as_ptr(),
+            number,
+            0,
+        )
+    };
+    if ret < 0 {
+        return Err(AvError(AVERROR(EINVAL)));
+    }
+    let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+    buf.truncate(len);
+    String::from_utf8(buf).map_err(|_| AvError(AVERROR(EINVAL)))
+}
+
+/// Allocate an output format context by the `format_name` or the extension of
+/// the `filename`.
+///
+/// The returned context must be freed with `avformat_free_context()`.
+pub fn alloc_output_context(
+    format_name: Option<&str>,
+    filename: Option<&str>,
+) -> Result<*mut AVFormatContext, AvError> {
+    let format_name = format_name
+        .map(CString::new)
+        .transpose()
+        .map_err(|_| AvError(AVERROR(EINVAL)))?;
+    let filename = filename
+        .map(CString::new)
+        .transpose()
+        .map_err(|_| AvError(AVERROR(EINVAL)))?;
+    let mut ctx = std::ptr::null_mut();
+    let ret = unsafe {
+        crate::avformat_alloc_output_context2(
+            &mut ctx,
+            std::ptr::null_mut(),
+            format_name
+                .as_ref()
+                .map_or(std::ptr::null(), |v| v.as_ptr()),
+            filename.as_ref().map_or(std::ptr::null(), |v| v.as_ptr()),
+        )
+    };
+    if ret < 0 {
+        Err(AvError(ret))
+    } else if ctx.is_null() {
+        Err(AvError(AVERROR_UNKNOWN))
+    } else {
+        Ok(ctx)
+    }
+}
 
 impl AVFormatContext {
     /// Returns the reference of the I/O context.
@@ -32,14 +142,119 @@ impl AVFormatContext {
     /// A list of all streams in the file.
     #[inline]
     pub fn streams(&self) -> &[&AVStream] {
-        unsafe { std::slice::from_raw_parts(self.streams as *const &AVStream, self.nb_streams()) }
+        if self.streams.is_null() || self.nb_streams == 0 {
+            &[]
+        } else {
+            unsafe {
+                std::slice::from_raw_parts(self.streams as *const &AVStream, self.nb_streams())
+            }
+        }
     }
 
-    /// A list of all streams in the file.
+    /// A mutable list of all streams in the file.
     #[inline]
-    pub fn streams_mut(&self) -> &[&mut AVStream] {
-        unsafe {
-            std::slice::from_raw_parts(self.streams as *const &mut AVStream, self.nb_streams())
+    pub fn streams_mut(&mut self) -> &mut [&mut AVStream] {
+        if self.streams.is_null() || self.nb_streams == 0 {
+            &mut []
+        } else {
+            unsafe {
+                std::slice::from_raw_parts_mut(
+                    self.streams as *mut &mut AVStream,
+                    self.nb_streams(),
+                )
+            }
+        }
+    }
+
+    /// Returns the best stream of the media type `ty` chosen by FFmpeg,
+    /// `None` if not found.
+    pub fn best_stream(&self, ty: AVMediaType) -> Option<&AVStream> {
+        let ret = unsafe {
+            crate::av_find_best_stream(
+                self as *const Self as *mut Self,
+                ty,
+                -1,
+                -1,
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        if ret < 0 {
+            None
+        } else {
+            self.streams().get(ret as usize).copied()
+        }
+    }
+
+    /// Returns an iterator over the streams of the media type `ty`.
+    pub fn streams_of(&self, ty: AVMediaType) -> impl Iterator<Item = &AVStream> {
+        self.streams()
+            .iter()
+            .copied()
+            .filter(move |st| st.codecpar().map(|par| par.codec_type) == Some(ty))
+    }
+
+    /// Returns the index of the default stream chosen by FFmpeg, video streams
+    /// are preferred over audio streams.
+    pub fn default_stream_index(&self) -> Option<usize> {
+        let ret = unsafe { crate::av_find_default_stream_index(self as *const Self as *mut Self) };
+        if ret < 0 {
+            None
+        } else {
+            Some(ret as usize)
+        }
+    }
+
+    /// Discard all internally buffered data of the demuxer without seeking,
+    /// such as after a discontinuity of a live stream.
+    ///
+    /// This is only valid for the input contexts.
+    pub fn flush(&mut self) -> Result<(), AvError> {
+        let ret = unsafe { crate::avformat_flush(self) };
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Guess the frame rate of the `stream` by the container and codec
+    /// information, returns `0/1` if indeterminate.
+    pub fn guess_frame_rate(&self, stream: &AVStream) -> AVRational {
+        let r = unsafe {
+            crate::av_guess_frame_rate(
+                self as *const Self as *mut Self,
+                stream as *const AVStream as *mut AVStream,
+                std::ptr::null_mut(),
+            )
+        };
+        if r.num == 0 || r.den == 0 {
+            AVRational { num: 0, den: 1 }
+        } else {
+            r
+        }
+    }
+
+    /// Guess the sample aspect ratio of the `stream` and the `frame`, the
+    /// stream SAR is preferred if set, returns `0/1` if unknown.
+    pub fn guess_sample_aspect_ratio(
+        &self,
+        stream: &AVStream,
+        frame: Option<&AVFrame>,
+    ) -> AVRational {
+        let r = unsafe {
+            crate::av_guess_sample_aspect_ratio(
+                self as *const Self as *mut Self,
+                stream as *const AVStream as *mut AVStream,
+                frame.map_or(std::ptr::null_mut(), |v| {
+                    v as *const AVFrame as *mut AVFrame
+                }),
+            )
+        };
+        if r.num <= 0 || r.den <= 0 {
+            AVRational { num: 0, den: 1 }
+        } else {
+            r
         }
     }
 
@@ -52,16 +267,27 @@ impl AVFormatContext {
     /// A list of all programs in the file.
     #[inline]
     pub fn programs(&self) -> &[&AVProgram] {
-        unsafe {
-            std::slice::from_raw_parts(self.programs as *const &AVProgram, self.nb_programs())
+        if self.programs.is_null() || self.nb_programs == 0 {
+            &[]
+        } else {
+            unsafe {
+                std::slice::from_raw_parts(self.programs as *const &AVProgram, self.nb_programs())
+            }
         }
     }
 
-    /// A list of all programs in the file.
+    /// A mutable list of all programs in the file.
     #[inline]
-    pub fn programs_mut(&self) -> &[&mut AVProgram] {
-        unsafe {
-            std::slice::from_raw_parts(self.programs as *const &mut AVProgram, self.nb_programs())
+    pub fn programs_mut(&mut self) -> &mut [&mut AVProgram] {
+        if self.programs.is_null() || self.nb_programs == 0 {
+            &mut []
+        } else {
+            unsafe {
+                std::slice::from_raw_parts_mut(
+                    self.programs as *mut &mut AVProgram,
+                    self.nb_programs(),
+                )
+            }
         }
     }
 
@@ -74,20 +300,198 @@ impl AVFormatContext {
     /// A list of all chapters in the file.
     #[inline]
     pub fn chapters(&self) -> &[&AVChapter] {
-        unsafe {
-            std::slice::from_raw_parts(self.chapters as *const &AVChapter, self.nb_chapters())
+        if self.chapters.is_null() || self.nb_chapters == 0 {
+            &[]
+        } else {
+            unsafe {
+                std::slice::from_raw_parts(self.chapters as *const &AVChapter, self.nb_chapters())
+            }
         }
     }
 
-    /// A list of all chapters in the file.
+    /// A mutable list of all chapters in the file.
+    #[inline]
+    pub fn chapters_mut(&mut self) -> &mut [&mut AVChapter] {
+        if self.chapters.is_null() || self.nb_chapters == 0 {
+            &mut []
+        } else {
+            unsafe {
+                std::slice::from_raw_parts_mut(
+                    self.chapters as *mut &mut AVChapter,
+                    self.nb_chapters(),
+                )
+            }
+        }
+    }
+
+    /// The metadata of the container.
     #[inline]
-    pub fn chapters_mut(&self) -> &[&mut AVChapter] {
+    pub fn metadata(&self) -> Option<&AVDictionary> {
+        if self.metadata.is_null() {
+            None
+        } else {
+            unsafe { Some(&*self.metadata) }
+        }
+    }
+
+    /// Mutable metadata of the container.
+    #[inline]
+    pub fn metadata_mut(&mut self) -> Option<&mut AVDictionary> {
+        if self.metadata.is_null() {
+            None
+        } else {
+            unsafe { Some(&mut *self.metadata) }
+        }
+    }
+
+    /// Returns a human readable summary of the container and the streams.
+    ///
+    /// Unlike `av_dump_format`, which writes to stderr, the summary is returned
+    /// as a `String` with one line for the container and one line per stream.
+    pub fn describe(&self) -> String {
+        let name = self.format_name();
+
+        let mut s = String::new();
+        let _ = write!(s, "{}, duration: ", name);
+        write_duration(&mut s, self.duration);
+        if self.bit_rate > 0 {
+            let _ = write!(s, ", bitrate: {} kb/s", self.bit_rate / 1000);
+        } else {
+            s.push_str(", bitrate: N/A");
+        }
+        for (i, stream) in self.streams().iter().enumerate() {
+            let _ = write!(s, "\n  Stream #{}: ", i);
+            match stream.codecpar() {
+                Some(par) => {
+                    let _ = write!(s, "{}", par);
+                }
+                None => s.push_str("unknown"),
+            }
+        }
+        s
+    }
+
+    /// Returns a report of the format and streams in the layout of
+    /// `av_dump_format()`, without printing to the stderr.
+    pub fn format_report(&self) -> String {
+        let is_output = self.iformat.is_null() && !self.oformat.is_null();
+        let mut s = String::new();
+        let _ = write!(
+            s,
+            "{} #0, {}",
+            if is_output { "Output" } else { "Input" },
+            self.format_name()
+        );
+        if !self.url.is_null() {
+            let url = unsafe { CStr::from_ptr(self.url).to_string_lossy() };
+            let _ = write!(s, ", {} '{}'", if is_output { "to" } else { "from" }, url);
+        }
+        s.push_str(":\n");
+        if !is_output {
+            s.push_str("  Duration: ");
+            write_duration(&mut s, self.duration);
+            if self.start_time != AV_NOPTS_VALUE {
+                let _ = write!(
+                    s,
+                    ", start: {}.{:06}",
+                    self.start_time / AV_TIME_BASE as i64,
+                    (self.start_time % AV_TIME_BASE as i64).abs()
+                );
+            }
+            if self.bit_rate > 0 {
+                let _ = write!(s, ", bitrate: {} kb/s", self.bit_rate / 1000);
+            } else {
+                s.push_str(", bitrate: N/A");
+            }
+            s.push('\n');
+        }
+        for (i, st) in self.streams().iter().enumerate() {
+            let _ = write!(s, "    Stream #0:{}", i);
+            if let Some(par) = st.codecpar() {
+                let _ = write!(s, ": {}", report_codecpar(par));
+                if par.codec_type == AVMediaType::AVMEDIA_TYPE_VIDEO {
+                    let fps = st.avg_frame_rate;
+                    if fps.num > 0 && fps.den > 0 {
+                        let _ = write!(s, ", {} fps", format_rate(fps.num, fps.den));
+                    }
+                    let tbr = st.r_frame_rate;
+                    if tbr.num > 0 && tbr.den > 0 {
+                        let _ = write!(s, ", {} tbr", format_rate(tbr.num, tbr.den));
+                    }
+                    if st.time_base.num > 0 && st.time_base.den > 0 {
+                        let _ = write!(
+                            s,
+                            ", {} tbn",
+                            format_rate(st.time_base.den, st.time_base.num)
+                        );
+                    }
+                }
+            }
+            s.push('\n');
+        }
+        s
+    }
+
+    fn format_name(&self) -> Cow<'static, str> {
         unsafe {
-            std::slice::from_raw_parts(self.chapters as *const &mut AVChapter, self.nb_chapters())
+            let name = if !self.iformat.is_null() {
+                (*self.iformat).name
+            } else if !self.oformat.is_null() {
+                (*self.oformat).name
+            } else {
+                std::ptr::null()
+            };
+            if name.is_null() {
+                "unknown".into()
+            } else {
+                CStr::from_ptr(name).to_string_lossy()
+            }
         }
     }
 }
 
+/// Write the `duration` in `AV_TIME_BASE` units as `HH:MM:SS.cc`.
+fn write_duration(s: &mut String, duration: i64) {
+    if duration == AV_NOPTS_VALUE {
+        s.push_str("N/A");
+    } else {
+        let secs = duration / AV_TIME_BASE as i64;
+        let us = duration % AV_TIME_BASE as i64;
+        let _ = write!(
+            s,
+            "{:02}:{:02}:{:02}.{:02}",
+            secs / 3600,
+            (secs / 60) % 60,
+            secs % 60,
+            (100 * us) / AV_TIME_BASE as i64
+        );
+    }
+}
+
+/// Format the rate like `av_dump_format()`, such as `25`, `29.97` or `90k`.
+fn format_rate(num: i32, den: i32) -> String {
+    let v = num as f64 / den as f64;
+    let rounded = (v * 100.0).round() as i64;
+    if rounded % 100 != 0 {
+        format!("{:.2}", v)
+    } else if rounded % (1000 * 100) == 0 {
+        format!("{}k", rounded / (1000 * 100))
+    } else {
+        format!("{}", rounded / 100)
+    }
+}
+
+/// Format the codec parameters like `av_dump_format()`, such as
+/// `Video: h264, yuv420p, 1920x1080` or `Audio: aac, 48000 Hz, 2 channels, fltp`.
+fn report_codecpar(par: &AVCodecParameters) -> String {
+    let s = par.to_string();
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => s,
+    }
+}
+
 impl AVStream {
     /// The context of the encoded stream.
     #[deprecated]
@@ -177,4 +581,798 @@ impl AVStream {
             }
         }
     }
+
+    /// Returns the duration of the stream in seconds, `None` if unknown.
+    pub fn duration_seconds(&self) -> Option<f64> {
+        self.ts_to_seconds(self.duration)
+    }
+
+    /// Returns the start time of the stream in seconds, `None` if unknown.
+    pub fn start_time_seconds(&self) -> Option<f64> {
+        self.ts_to_seconds(self.start_time)
+    }
+
+    /// The average frame rate of the stream, `0/0` if unknown.
+    #[inline]
+    pub fn frame_rate(&self) -> AVRational {
+        self.avg_frame_rate
+    }
+
+    fn ts_to_seconds(&self, ts: i64) -> Option<f64> {
+        if ts == AV_NOPTS_VALUE || self.time_base.den == 0 {
+            None
+        } else {
+            Some(ts as f64 * f64::from(self.time_base))
+        }
+    }
+
+    /// Returns a compact line describing the stream, such as
+    /// `#0: video h264 1920x1080 @ 30000/1001 fps` or
+    /// `#1: audio aac 48000 Hz 2 channels`.
+    pub fn summary(&self) -> String {
+        let mut s = format!("#{}: ", self.index);
+        let par = match self.codecpar() {
+            Some(par) => par,
+            None => {
+                s.push_str("unknown");
+                return s;
+            }
+        };
+        let _ = write!(s, "{} {}", par.codec_type, par.codec_id.get_name());
+        match par.codec_type {
+            AVMediaType::AVMEDIA_TYPE_VIDEO => {
+                let _ = write!(s, " {}x{}", par.width, par.height);
+                let fps = self.avg_frame_rate;
+                if fps.num > 0 && fps.den > 0 {
+                    let _ = write!(s, " @ {}/{} fps", fps.num, fps.den);
+                }
+            }
+            AVMediaType::AVMEDIA_TYPE_AUDIO => {
+                let _ = write!(s, " {} Hz {} channels", par.sample_rate, par.channels);
+            }
+            _ => {}
+        }
+        s
+    }
+
+    /// Returns the data of the side data of the type `ty` by scanning the
+    /// `side_data` array, `None` if absent.
+    pub fn side_data_of(&self, ty: AVPacketSideDataType) -> Option<&[u8]> {
+        crate::avcodec::find_side_data(self.side_data(), ty)
+    }
+
+    /// Returns the rotation angle in degrees of the display matrix side data,
+    /// counterclockwise, `None` if absent or too small.
+    pub fn rotation(&self) -> Option<f64> {
+        let data = self.side_data_of(AVPacketSideDataType::AV_PKT_DATA_DISPLAYMATRIX)?;
+        if data.len() < 36 {
+            return None;
+        }
+        let mut matrix = [0i32; 9];
+        for (v, bytes) in matrix.iter_mut().zip(data.chunks_exact(4)) {
+            *v = i32::from_ne_bytes(bytes.try_into().unwrap());
+        }
+        Some(crate::av_display_rotation_get_safe(&matrix))
+    }
+
+    /// Returns the side data of the `kind`, such as the display matrix of
+    /// `AV_PKT_DATA_DISPLAYMATRIX`, `None` if absent.
+    pub fn get_side_data(&self, kind: AVPacketSideDataType) -> Option<&[u8]> {
+        let mut size = 0;
+        unsafe {
+            let data = crate::av_stream_get_side_data(self, kind, &mut size);
+            if data.is_null() {
+                None
+            } else {
+                Some(std::slice::from_raw_parts(data, size as usize))
+            }
+        }
+    }
+}
+
+impl AVChapter {
+    /// Unique ID to identify the chapter.
+    #[inline]
+    pub fn id(&self) -> i64 {
+        i64::from(self.id)
+    }
+
+    /// Time base in which the start/end timestamps are specified.
+    #[inline]
+    pub fn time_base(&self) -> AVRational {
+        self.time_base
+    }
+
+    /// Chapter start time in `time_base` units.
+    #[inline]
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    /// Chapter end time in `time_base` units.
+    #[inline]
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+
+    /// The metadata of the chapter.
+    #[inline]
+    pub fn metadata(&self) -> Option<&AVDictionary> {
+        if self.metadata.is_null() {
+            None
+        } else {
+            unsafe { Some(&*self.metadata) }
+        }
+    }
+
+    /// Chapter start time in seconds.
+    pub fn start_seconds(&self) -> f64 {
+        self.start as f64 * f64::from(self.time_base)
+    }
+
+    /// Chapter end time in seconds.
+    pub fn end_seconds(&self) -> f64 {
+        self.end as f64 * f64::from(self.time_base)
+    }
+}
+
+impl AVProgram {
+    /// The ID of the program.
+    #[inline]
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    /// The number of the program, such as the MPEG-TS program number.
+    #[inline]
+    pub fn program_num(&self) -> i32 {
+        self.program_num
+    }
+
+    /// The indices of the streams belonging to the program.
+    #[inline]
+    pub fn stream_indices(&self) -> &[u32] {
+        if self.stream_index.is_null() || self.nb_stream_indexes == 0 {
+            &[]
+        } else {
+            unsafe {
+                std::slice::from_raw_parts(self.stream_index, self.nb_stream_indexes as usize)
+            }
+        }
+    }
+
+    /// The metadata of the program.
+    #[inline]
+    pub fn metadata(&self) -> Option<&AVDictionary> {
+        if self.metadata.is_null() {
+            None
+        } else {
+            unsafe { Some(&*self.metadata) }
+        }
+    }
+}
+
+impl AVOutputFormat {
+    /// Returns `true` if the `codec_id` can be stored in the container with
+    /// the `std_compliance` level, such as `FF_COMPLIANCE_STRICT`.
+    pub fn query_codec(&self, codec_id: AVCodecID, std_compliance: i32) -> bool {
+        unsafe { crate::avformat_query_codec(self, codec_id, std_compliance) > 0 }
+    }
+
+    /// The default video codec of the container.
+    #[inline]
+    pub fn default_video_codec(&self) -> AVCodecID {
+        self.video_codec
+    }
+
+    /// The default audio codec of the container.
+    #[inline]
+    pub fn default_audio_codec(&self) -> AVCodecID {
+        self.audio_codec
+    }
+
+    /// Returns the codec id of the `tag` in the tag tables of the container,
+    /// `AV_CODEC_ID_NONE` if not found.
+    pub fn codec_id_for_tag(&self, tag: u32) -> AVCodecID {
+        // The tag tables of the container are terminated by FFmpeg.
+        unsafe { crate::codec_tag_to_id(self.codec_tag, tag) }
+    }
+
+    /// Returns the tag of the codec `id` in the tag tables of the container,
+    /// 0 if not found.
+    pub fn codec_tag_for_id(&self, id: AVCodecID) -> u32 {
+        unsafe { crate::codec_id_to_tag(self.codec_tag, id) }
+    }
+}
+
+/// The mapping from the input stream indices to the output stream indices.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamMapping {
+    mapping: Vec<i32>,
+}
+
+impl StreamMapping {
+    /// Build the mapping for the streams of `ictx`, the streams whose media
+    /// type is rejected by the `predicate` or without the codec parameters
+    /// are dropped.
+    pub fn new<F>(ictx: &AVFormatContext, mut predicate: F) -> Self
+    where
+        F: FnMut(AVMediaType) -> bool,
+    {
+        let mut next_index = 0;
+        let mapping = ictx
+            .streams()
+            .iter()
+            .map(|st| match st.codecpar() {
+                Some(par) if predicate(par.codec_type) => {
+                    next_index += 1;
+                    next_index - 1
+                }
+                _ => -1,
+            })
+            .collect();
+        Self { mapping }
+    }
+
+    /// Returns the output index of the input stream, `None` if dropped.
+    pub fn map(&self, input_index: usize) -> Option<usize> {
+        match self.mapping.get(input_index) {
+            Some(&v) if v >= 0 => Some(v as usize),
+            _ => None,
+        }
+    }
+
+    /// Number of the output streams.
+    pub fn nb_outputs(&self) -> usize {
+        self.mapping.iter().filter(|&&v| v >= 0).count()
+    }
+
+    /// Returns the raw mapping, `-1` means the stream is dropped.
+    pub fn as_slice(&self) -> &[i32] {
+        &self.mapping
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{avformat_alloc_context, avformat_free_context, avformat_new_stream, AVMediaType};
+
+    #[test]
+    fn test_guess_output_format() {
+        let name = |fmt: &AVOutputFormat| unsafe {
+            CStr::from_ptr(fmt.name).to_string_lossy().into_owned()
+        };
+        let fmt = guess_output_format(Some("mp4"), None, None).unwrap();
+        assert_eq!(name(fmt), "mp4");
+        let fmt = guess_output_format(None, Some("output.mkv"), None).unwrap();
+        assert_eq!(name(fmt), "matroska");
+        assert!(guess_output_format(Some("no-such-format"), None, None).is_none());
+    }
+
+    #[test]
+    fn test_muxers_demuxers() {
+        let name = |name: *const libc::c_char| unsafe {
+            CStr::from_ptr(name).to_string_lossy().into_owned()
+        };
+        assert!(muxers()
+            .map(|fmt| name(fmt.name))
+            .any(|v| v.contains("mp4") || v.contains("mov")));
+        assert!(demuxers().map(|fmt| name(fmt.name)).any(|v| v == "s16le"));
+    }
+
+    #[test]
+    fn test_describe() {
+        unsafe {
+            let ctx = avformat_alloc_context();
+            for &codec_type in [
+                AVMediaType::AVMEDIA_TYPE_VIDEO,
+                AVMediaType::AVMEDIA_TYPE_AUDIO,
+            ]
+            .iter()
+            {
+                let st = avformat_new_stream(ctx, std::ptr::null());
+                (*(*st).codecpar).codec_type = codec_type;
+            }
+            let summary = (*ctx).describe();
+            assert_eq!(summary.lines().count(), 3);
+            assert_eq!(summary.matches("Stream #").count(), 2);
+            assert!(summary.contains("video"));
+            assert!(summary.contains("audio"));
+            avformat_free_context(ctx);
+        }
+    }
+
+    #[test]
+    fn test_stream_mapping() {
+        use AVMediaType::*;
+        unsafe {
+            let ctx = avformat_alloc_context();
+            for &codec_type in [
+                AVMEDIA_TYPE_VIDEO,
+                AVMEDIA_TYPE_DATA,
+                AVMEDIA_TYPE_AUDIO,
+                AVMEDIA_TYPE_SUBTITLE,
+            ]
+            .iter()
+            {
+                let st = avformat_new_stream(ctx, std::ptr::null());
+                (*(*st).codecpar).codec_type = codec_type;
+            }
+            let mapping = StreamMapping::new(&*ctx, |t| {
+                t == AVMEDIA_TYPE_AUDIO || t == AVMEDIA_TYPE_VIDEO || t == AVMEDIA_TYPE_SUBTITLE
+            });
+            assert_eq!(mapping.as_slice(), &[0, -1, 1, 2]);
+            assert_eq!(mapping.nb_outputs(), 3);
+            assert_eq!(mapping.map(0), Some(0));
+            assert_eq!(mapping.map(1), None);
+            assert_eq!(mapping.map(2), Some(1));
+            assert_eq!(mapping.map(3), Some(2));
+            assert_eq!(mapping.map(4), None);
+
+            let mapping = StreamMapping::new(&*ctx, |t| t == AVMEDIA_TYPE_AUDIO);
+            assert_eq!(mapping.as_slice(), &[-1, -1, 0, -1]);
+
+            let st = *(*ctx).streams;
+            let par = std::mem::replace(&mut (*st).codecpar, std::ptr::null_mut());
+            let mapping = StreamMapping::new(&*ctx, |_| true);
+            assert_eq!(mapping.as_slice(), &[-1, 0, 1, 2]);
+            (*st).codecpar = par;
+            avformat_free_context(ctx);
+        }
+    }
+
+    #[test]
+    fn test_alloc_output_context() {
+        unsafe {
+            let ctx = alloc_output_context(Some("mp4"), None).unwrap();
+            assert_eq!(CStr::from_ptr((*(*ctx).oformat).name).to_str(), Ok("mp4"));
+            avformat_free_context(ctx);
+
+            let ctx = alloc_output_context(None, Some("output.mkv")).unwrap();
+            assert_eq!(
+                CStr::from_ptr((*(*ctx).oformat).name).to_str(),
+                Ok("matroska")
+            );
+            avformat_free_context(ctx);
+        }
+        assert!(alloc_output_context(Some("no-such-format"), None).is_err());
+        assert!(alloc_output_context(None, None).is_err());
+    }
+
+    #[test]
+    fn test_query_codec() {
+        use crate::{AVCodecID::*, FF_COMPLIANCE_NORMAL, FF_COMPLIANCE_STRICT};
+
+        let mp4 = guess_output_format(Some("mp4"), None, None).unwrap();
+        assert!(mp4.query_codec(AV_CODEC_ID_H264, FF_COMPLIANCE_STRICT));
+        assert!(mp4.query_codec(AV_CODEC_ID_AAC, FF_COMPLIANCE_NORMAL));
+        assert!(!mp4.query_codec(AV_CODEC_ID_PCM_S16LE, FF_COMPLIANCE_STRICT));
+
+        let mkv = guess_output_format(Some("matroska"), None, None).unwrap();
+        assert!(mkv.query_codec(AV_CODEC_ID_FLAC, FF_COMPLIANCE_STRICT));
+        assert_ne!(mkv.default_video_codec(), AV_CODEC_ID_NONE);
+        assert_ne!(mkv.default_audio_codec(), AV_CODEC_ID_NONE);
+    }
+
+    #[test]
+    fn test_default_stream_index() {
+        unsafe {
+            let ctx = avformat_alloc_context();
+            assert_eq!((*ctx).default_stream_index(), None);
+            for &codec_type in [
+                AVMediaType::AVMEDIA_TYPE_AUDIO,
+                AVMediaType::AVMEDIA_TYPE_VIDEO,
+            ]
+            .iter()
+            {
+                let st = avformat_new_stream(ctx, std::ptr::null());
+                let par = &mut *(*st).codecpar;
+                par.codec_type = codec_type;
+                if codec_type == AVMediaType::AVMEDIA_TYPE_VIDEO {
+                    par.width = 320;
+                    par.height = 240;
+                } else {
+                    par.sample_rate = 48000;
+                }
+            }
+            assert_eq!((*ctx).default_stream_index(), Some(1));
+            avformat_free_context(ctx);
+        }
+    }
+
+    #[test]
+    fn test_best_stream() {
+        use AVMediaType::*;
+
+        unsafe {
+            let ctx = avformat_alloc_context();
+            assert!((*ctx).best_stream(AVMEDIA_TYPE_VIDEO).is_none());
+            for &(codec_type, sample_rate) in [
+                (AVMEDIA_TYPE_AUDIO, 0),
+                (AVMEDIA_TYPE_VIDEO, 0),
+                (AVMEDIA_TYPE_AUDIO, 48000),
+            ]
+            .iter()
+            {
+                let st = avformat_new_stream(ctx, std::ptr::null());
+                let par = &mut *(*st).codecpar;
+                par.codec_type = codec_type;
+                if codec_type == AVMEDIA_TYPE_AUDIO && sample_rate > 0 {
+                    par.sample_rate = sample_rate;
+                    par.channels = 2;
+                }
+            }
+            let ctx_ref = &*ctx;
+            assert_eq!(ctx_ref.best_stream(AVMEDIA_TYPE_VIDEO).unwrap().index, 1);
+            assert_eq!(ctx_ref.best_stream(AVMEDIA_TYPE_AUDIO).unwrap().index, 2);
+            assert!(ctx_ref.best_stream(AVMEDIA_TYPE_SUBTITLE).is_none());
+            let audio: Vec<i32> = ctx_ref
+                .streams_of(AVMEDIA_TYPE_AUDIO)
+                .map(|st| st.index)
+                .collect();
+            assert_eq!(audio, [0, 2]);
+            assert_eq!(ctx_ref.streams_of(AVMEDIA_TYPE_SUBTITLE).count(), 0);
+            avformat_free_context(ctx);
+        }
+    }
+
+    #[test]
+    fn test_streams_mut() {
+        unsafe {
+            let ctx = avformat_alloc_context();
+            let ctx_mut = &mut *ctx;
+            assert!(ctx_mut.streams().is_empty());
+            assert!(ctx_mut.streams_mut().is_empty());
+            assert!(ctx_mut.programs_mut().is_empty());
+            assert!(ctx_mut.chapters_mut().is_empty());
+            avformat_new_stream(ctx, std::ptr::null());
+            avformat_new_stream(ctx, std::ptr::null());
+
+            let ctx_mut = &mut *ctx;
+            for st in ctx_mut.streams_mut().iter_mut() {
+                st.time_base = AVRational { num: 1, den: 90000 };
+            }
+            ctx_mut.streams_mut()[1].id = 7;
+            let streams = ctx_mut.streams();
+            assert_eq!(streams.len(), 2);
+            assert!(streams
+                .iter()
+                .all(|st| st.time_base == AVRational { num: 1, den: 90000 }));
+            assert_eq!(streams[1].id, 7);
+            avformat_free_context(ctx);
+        }
+    }
+
+    #[test]
+    fn test_format_context_metadata() {
+        let mut ctx: AVFormatContext = unsafe { std::mem::zeroed() };
+        assert!(ctx.metadata().is_none());
+        assert!(ctx.metadata_mut().is_none());
+        unsafe {
+            let key = CString::new("title").unwrap();
+            let value = CString::new("test").unwrap();
+            crate::av_dict_set(&mut ctx.metadata, key.as_ptr(), value.as_ptr(), 0);
+        }
+        assert_eq!(ctx.metadata().and_then(|m| m.get("title")).unwrap(), "test");
+        unsafe { crate::av_dict_free(&mut ctx.metadata) };
+        assert!(ctx.metadata().is_none());
+    }
+
+    #[test]
+    fn test_guess_frame_rate() {
+        unsafe {
+            let ctx = avformat_alloc_context();
+            let st = &mut *avformat_new_stream(ctx, std::ptr::null());
+            (*st.codecpar).codec_type = AVMediaType::AVMEDIA_TYPE_VIDEO;
+            assert_eq!((*ctx).guess_frame_rate(st), AVRational { num: 0, den: 1 });
+            st.r_frame_rate = AVRational { num: 25, den: 1 };
+            st.avg_frame_rate = AVRational { num: 25, den: 1 };
+            assert_eq!((*ctx).guess_frame_rate(st), AVRational { num: 25, den: 1 });
+            avformat_free_context(ctx);
+        }
+    }
+
+    #[test]
+    fn test_guess_sample_aspect_ratio() {
+        unsafe {
+            let ctx = avformat_alloc_context();
+            let st = &mut *avformat_new_stream(ctx, std::ptr::null());
+            (*st.codecpar).codec_type = AVMediaType::AVMEDIA_TYPE_VIDEO;
+            assert_eq!(
+                (*ctx).guess_sample_aspect_ratio(st, None),
+                AVRational { num: 0, den: 1 }
+            );
+            (*st.codecpar).sample_aspect_ratio = AVRational { num: 10, den: 11 };
+            assert_eq!(
+                (*ctx).guess_sample_aspect_ratio(st, None),
+                AVRational { num: 10, den: 11 }
+            );
+            st.sample_aspect_ratio = AVRational { num: 64, den: 48 };
+            assert_eq!(
+                (*ctx).guess_sample_aspect_ratio(st, None),
+                AVRational { num: 4, den: 3 }
+            );
+            avformat_free_context(ctx);
+        }
+    }
+
+    #[test]
+    fn test_get_frame_filename() {
+        assert_eq!(
+            get_frame_filename("img-%03d.jpg", 7).unwrap(),
+            "img-007.jpg"
+        );
+        assert_eq!(
+            get_frame_filename("img-%d.jpg", 1234).unwrap(),
+            "img-1234.jpg"
+        );
+        assert!(get_frame_filename("img.jpg", 7).is_err());
+        assert!(get_frame_filename("img-%d-%d.jpg", 7).is_err());
+    }
+
+    #[test]
+    fn test_format_report() {
+        unsafe {
+            let ctx = alloc_output_context(Some("mp4"), None).unwrap();
+            let st = &mut *avformat_new_stream(ctx, std::ptr::null());
+            st.avg_frame_rate = AVRational { num: 25, den: 1 };
+            st.time_base = AVRational { num: 1, den: 90000 };
+            let par = &mut *st.codecpar;
+            par.codec_type = AVMediaType::AVMEDIA_TYPE_VIDEO;
+            par.codec_id = crate::AVCodecID::AV_CODEC_ID_H264;
+            par.format = crate::AVPixelFormat::AV_PIX_FMT_YUV420P as i32;
+            par.width = 1920;
+            par.height = 1080;
+            let st = &mut *avformat_new_stream(ctx, std::ptr::null());
+            let par = &mut *st.codecpar;
+            par.codec_type = AVMediaType::AVMEDIA_TYPE_AUDIO;
+            par.codec_id = crate::AVCodecID::AV_CODEC_ID_AAC;
+            par.format = crate::AVSampleFormat::AV_SAMPLE_FMT_FLTP as i32;
+            par.sample_rate = 48000;
+            par.channels = 2;
+
+            let report = (*ctx).format_report();
+            assert!(report.starts_with("Output #0, mp4"));
+            assert!(report
+                .contains("    Stream #0:0: Video: h264, yuv420p, 1920x1080, 25 fps, 90k tbn\n"));
+            assert!(report.contains("    Stream #0:1: Audio: aac, 48000 Hz, 2 channels, fltp\n"));
+            avformat_free_context(ctx);
+        }
+    }
+
+    #[test]
+    fn test_interrupt_read_frame() {
+        use crate::{
+            av_find_input_format, av_packet_alloc, av_packet_free, av_packet_unref, av_read_frame,
+            avformat_close_input, avformat_open_input, AVERROR_EXIT,
+        };
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let path = std::env::temp_dir().join("ffav-sys-test-interrupt-read-frame.pcm");
+        std::fs::write(&path, vec![0u8; 1 << 20]).unwrap();
+        let url = CString::new(path.to_str().unwrap()).unwrap();
+        let abort = Arc::new(AtomicBool::new(false));
+        // The callback outlives the context, which only holds the raw callback.
+        let interrupt = InterruptCallback::new({
+            let abort = abort.clone();
+            move || abort.load(Ordering::SeqCst)
+        });
+        unsafe {
+            let mut ctx = avformat_alloc_context();
+            (*ctx).interrupt_callback = interrupt.as_raw();
+            let ret = avformat_open_input(
+                &mut ctx,
+                url.as_ptr(),
+                av_find_input_format(b"s16le\0".as_ptr() as _),
+                std::ptr::null_mut(),
+            );
+            assert_eq!(ret, 0);
+
+            let mut pkt = av_packet_alloc();
+            assert_eq!(av_read_frame(ctx, pkt), 0);
+            av_packet_unref(pkt);
+            abort.store(true, Ordering::SeqCst);
+            let ret = loop {
+                let ret = av_read_frame(ctx, pkt);
+                av_packet_unref(pkt);
+                if ret < 0 {
+                    break ret;
+                }
+            };
+            assert_eq!(ret, AVERROR_EXIT);
+            av_packet_free(&mut pkt);
+            avformat_close_input(&mut ctx);
+        }
+        drop(interrupt);
+        assert_eq!(Arc::strong_count(&abort), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_flush() {
+        use crate::{
+            av_find_input_format, av_packet_alloc, av_packet_free, av_read_frame,
+            avformat_close_input, avformat_open_input,
+        };
+
+        let path = std::env::temp_dir().join("ffav-sys-test-flush.pcm");
+        std::fs::write(&path, vec![0u8; 1 << 16]).unwrap();
+        let url = CString::new(path.to_str().unwrap()).unwrap();
+        unsafe {
+            let mut ctx = std::ptr::null_mut();
+            let ret = avformat_open_input(
+                &mut ctx,
+                url.as_ptr(),
+                av_find_input_format(b"s16le\0".as_ptr() as _),
+                std::ptr::null_mut(),
+            );
+            assert_eq!(ret, 0);
+            let mut pkt = av_packet_alloc();
+            assert_eq!(av_read_frame(ctx, pkt), 0);
+            (*ctx).flush().unwrap();
+            crate::av_packet_unref(pkt);
+            assert_eq!(av_read_frame(ctx, pkt), 0);
+            av_packet_free(&mut pkt);
+            avformat_close_input(&mut ctx);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_stream_get_side_data() {
+        use crate::{av_display_rotation_set, av_stream_new_side_data};
+        use AVPacketSideDataType::*;
+
+        unsafe {
+            let ctx = avformat_alloc_context();
+            let st = avformat_new_stream(ctx, std::ptr::null());
+            assert!((*st).get_side_data(AV_PKT_DATA_DISPLAYMATRIX).is_none());
+            let data = av_stream_new_side_data(st, AV_PKT_DATA_DISPLAYMATRIX, 36);
+            av_display_rotation_set(data as *mut i32, 90.0);
+
+            let matrix = (*st).get_side_data(AV_PKT_DATA_DISPLAYMATRIX).unwrap();
+            let matrix: Vec<i32> = matrix
+                .chunks_exact(4)
+                .map(|v| i32::from_ne_bytes(v.try_into().unwrap()))
+                .collect();
+            assert_eq!(matrix.len(), 9);
+            assert_eq!(matrix[8], 1 << 30);
+            assert!((*st).get_side_data(AV_PKT_DATA_CPB_PROPERTIES).is_none());
+            avformat_free_context(ctx);
+        }
+    }
+
+    #[test]
+    fn test_stream_side_data_of() {
+        use AVPacketSideDataType::*;
+
+        let mut matrix = [0u8; 36];
+        let mut gain = [0u8; 16];
+        let mut side_data = [
+            AVPacketSideData {
+                data: matrix.as_mut_ptr(),
+                size: matrix.len() as _,
+                type_: AV_PKT_DATA_DISPLAYMATRIX,
+            },
+            AVPacketSideData {
+                data: gain.as_mut_ptr(),
+                size: gain.len() as _,
+                type_: AV_PKT_DATA_REPLAYGAIN,
+            },
+        ];
+        let mut st: AVStream = unsafe { std::mem::zeroed() };
+        assert!(st.side_data_of(AV_PKT_DATA_DISPLAYMATRIX).is_none());
+        st.side_data = side_data.as_mut_ptr();
+        st.nb_side_data = 2;
+        assert_eq!(
+            st.side_data_of(AV_PKT_DATA_DISPLAYMATRIX).map(|v| v.len()),
+            Some(36)
+        );
+        assert_eq!(
+            st.side_data_of(AV_PKT_DATA_REPLAYGAIN).map(|v| v.len()),
+            Some(16)
+        );
+        assert!(st.side_data_of(AV_PKT_DATA_CPB_PROPERTIES).is_none());
+    }
+
+    #[test]
+    fn test_stream_rotation() {
+        use crate::av_display_rotation_set;
+        use AVPacketSideDataType::*;
+
+        let mut matrix = [0i32; 9];
+        unsafe { av_display_rotation_set(matrix.as_mut_ptr(), 90.0) };
+        let mut data: Vec<u8> = matrix
+            .iter()
+            .flat_map(|v| v.to_ne_bytes().to_vec())
+            .collect();
+        let mut side_data = AVPacketSideData {
+            data: data.as_mut_ptr(),
+            size: data.len() as _,
+            type_: AV_PKT_DATA_DISPLAYMATRIX,
+        };
+        let mut st: AVStream = unsafe { std::mem::zeroed() };
+        assert!(st.rotation().is_none());
+        st.side_data = &mut side_data;
+        st.nb_side_data = 1;
+        assert!((st.rotation().unwrap() - 90.0).abs() < 1e-6);
+        unsafe { (*st.side_data).size = 32 };
+        assert!(st.rotation().is_none());
+    }
+
+    #[test]
+    fn test_stream_summary() {
+        unsafe {
+            let ctx = avformat_alloc_context();
+            let st = &mut *avformat_new_stream(ctx, std::ptr::null());
+            st.avg_frame_rate = AVRational {
+                num: 30000,
+                den: 1001,
+            };
+            let par = &mut *st.codecpar;
+            par.codec_type = AVMediaType::AVMEDIA_TYPE_VIDEO;
+            par.codec_id = AVCodecID::AV_CODEC_ID_H264;
+            par.width = 1920;
+            par.height = 1080;
+            assert_eq!(st.summary(), "#0: video h264 1920x1080 @ 30000/1001 fps");
+
+            let st = &mut *avformat_new_stream(ctx, std::ptr::null());
+            let par = &mut *st.codecpar;
+            par.codec_type = AVMediaType::AVMEDIA_TYPE_AUDIO;
+            par.codec_id = AVCodecID::AV_CODEC_ID_AAC;
+            par.sample_rate = 48000;
+            par.channels = 2;
+            assert_eq!(st.summary(), "#1: audio aac 48000 Hz 2 channels");
+            avformat_free_context(ctx);
+        }
+    }
+
+    #[test]
+    fn test_stream_timing() {
+        let mut st: AVStream = unsafe { std::mem::zeroed() };
+        st.time_base = AVRational { num: 1, den: 1000 };
+        st.duration = 5000;
+        st.start_time = AV_NOPTS_VALUE;
+        st.avg_frame_rate = AVRational { num: 25, den: 1 };
+        assert_eq!(st.duration_seconds(), Some(5.0));
+        assert_eq!(st.start_time_seconds(), None);
+        assert_eq!(st.frame_rate(), AVRational { num: 25, den: 1 });
+        st.start_time = 1500;
+        assert_eq!(st.start_time_seconds(), Some(1.5));
+        st.duration = AV_NOPTS_VALUE;
+        assert_eq!(st.duration_seconds(), None);
+    }
+
+    #[test]
+    fn test_chapter() {
+        let mut chapter: AVChapter = unsafe { std::mem::zeroed() };
+        chapter.id = 3;
+        chapter.time_base = AVRational { num: 1, den: 1000 };
+        chapter.start = 1500;
+        chapter.end = 62250;
+        assert_eq!(chapter.id(), 3);
+        assert_eq!(chapter.time_base(), AVRational { num: 1, den: 1000 });
+        assert_eq!(chapter.start(), 1500);
+        assert_eq!(chapter.end(), 62250);
+        assert_eq!(chapter.start_seconds(), 1.5);
+        assert_eq!(chapter.end_seconds(), 62.25);
+        assert!(chapter.metadata().is_none());
+    }
+
+    #[test]
+    fn test_program() {
+        let mut indices = [0u32, 2];
+        let mut program: AVProgram = unsafe { std::mem::zeroed() };
+        program.id = 1;
+        program.program_num = 4096;
+        assert!(program.stream_indices().is_empty());
+        program.stream_index = indices.as_mut_ptr();
+        program.nb_stream_indexes = indices.len() as _;
+        assert_eq!(program.id(), 1);
+        assert_eq!(program.program_num(), 4096);
+        assert_eq!(program.stream_indices(), [0, 2]);
+        assert!(program.metadata().is_none());
+    }
 }
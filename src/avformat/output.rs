@@ -0,0 +1,169 @@
+use crate::{
+    alloc_output_context, av_interleaved_write_frame, av_write_frame, av_write_trailer,
+    avformat_free_context, avformat_write_header, avio_closep, AVFormatContext, AVPacket, AvError,
+    InterruptCallback, AVFMT_NOFILE,
+};
+
+/// The output media file context.
+#[derive(Debug)]
+pub struct OutputContext {
+    ptr: *mut AVFormatContext,
+    // Dropped after the context, the I/O context may hold a copy of any of
+    // the raw callbacks.
+    interrupts: Vec<InterruptCallback>,
+}
+
+impl OutputContext {
+    /// Create a new output context by the `format_name` or the extension of the `filename`.
+    pub fn new(format_name: Option<&str>, filename: Option<&str>) -> Result<Self, AvError> {
+        alloc_output_context(format_name, filename).map(|ptr| Self {
+            ptr,
+            interrupts: Vec::new(),
+        })
+    }
+
+    /// Returns the raw pointer of the format context.
+    pub fn as_ptr(&self) -> *const AVFormatContext {
+        self.ptr
+    }
+
+    /// Returns the mutable raw pointer of the format context.
+    pub fn as_mut_ptr(&mut self) -> *mut AVFormatContext {
+        self.ptr
+    }
+
+    /// Set the callback to abort the blocking operations, such as opening
+    /// the I/O and writing the packets, when it returns `true`.
+    ///
+    /// The callback is copied by the I/O context on open, so it must be set
+    /// before opening the I/O. The closure is owned by the context and
+    /// dropped with it.
+    pub fn set_interrupt<F>(&mut self, cb: F)
+    where
+        F: Fn() -> bool + Send + 'static,
+    {
+        let cb = InterruptCallback::new(cb);
+        unsafe {
+            (*self.ptr).interrupt_callback = cb.as_raw();
+            // The replaced callbacks must stay alive while the opened I/O
+            // context may still hold their `opaque`.
+            if (*self.ptr).pb.is_null() {
+                self.interrupts.clear();
+            }
+        }
+        self.interrupts.push(cb);
+    }
+
+    /// Allocate the stream private data and write the stream header.
+    pub fn write_header(&mut self) -> Result<(), AvError> {
+        let ret = unsafe { avformat_write_header(self.ptr, std::ptr::null_mut()) };
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Write a packet to the output.
+    ///
+    /// If `interleaved` is `true`, the packet is buffered and reordered by the
+    /// muxer to ensure the packets of all streams are written in increasing
+    /// dts order, the ownership of the packet data is taken and `pkt` is
+    /// reset to blank.
+    ///
+    /// Otherwise the packet is passed directly to the muxer without any
+    /// buffering, the caller must take care of the interleaving and the `pkt`
+    /// is left untouched.
+    pub fn write_frame(&mut self, pkt: &mut AVPacket, interleaved: bool) -> Result<(), AvError> {
+        let ret = unsafe {
+            if interleaved {
+                av_interleaved_write_frame(self.ptr, pkt)
+            } else {
+                av_write_frame(self.ptr, pkt)
+            }
+        };
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Flush the buffered packets and write the stream trailer.
+    pub fn write_trailer(&mut self) -> Result<(), AvError> {
+        let ret = unsafe { av_write_trailer(self.ptr) };
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Drop for OutputContext {
+    fn drop(&mut self) {
+        unsafe {
+            let oformat = (*self.ptr).oformat;
+            if !oformat.is_null() && ((*oformat).flags & AVFMT_NOFILE) == 0 {
+                avio_closep(&mut (*self.ptr).pb);
+            }
+            avformat_free_context(self.ptr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        av_new_packet, av_packet_alloc, av_packet_free, avformat_new_stream, AVCodecID,
+        AVMediaType, AVRational,
+    };
+
+    #[test]
+    fn test_set_interrupt() {
+        use std::sync::Arc;
+
+        let flag = Arc::new(());
+        let mut octx = OutputContext::new(Some("null"), None).unwrap();
+        for _ in 0..2 {
+            let flag = flag.clone();
+            octx.set_interrupt(move || Arc::strong_count(&flag) == 0);
+        }
+        let raw = unsafe { (*octx.as_ptr()).interrupt_callback };
+        assert_eq!(unsafe { raw.callback.unwrap()(raw.opaque) }, 0);
+        assert_eq!(Arc::strong_count(&flag), 2);
+        assert_eq!(octx.interrupts.len(), 1);
+        drop(octx);
+        assert_eq!(Arc::strong_count(&flag), 1);
+    }
+
+    #[test]
+    fn test_write_frame() {
+        for &interleaved in [true, false].iter() {
+            let mut octx = OutputContext::new(Some("null"), None).unwrap();
+            unsafe {
+                let st = avformat_new_stream(octx.as_mut_ptr(), std::ptr::null());
+                (*st).time_base = AVRational { num: 1, den: 25 };
+                let par = &mut *(*st).codecpar;
+                par.codec_type = AVMediaType::AVMEDIA_TYPE_VIDEO;
+                par.codec_id = AVCodecID::AV_CODEC_ID_RAWVIDEO;
+                par.width = 16;
+                par.height = 16;
+            }
+            octx.write_header().unwrap();
+
+            let mut pkt = unsafe { av_packet_alloc() };
+            unsafe {
+                assert_eq!(av_new_packet(pkt, 16), 0);
+                (*pkt).pts = 0;
+                (*pkt).dts = 0;
+                (*pkt).duration = 1;
+                (*pkt).stream_index = 0;
+                octx.write_frame(&mut *pkt, interleaved).unwrap();
+                av_packet_free(&mut pkt);
+            }
+            octx.write_trailer().unwrap();
+        }
+    }
+}
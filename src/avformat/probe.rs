@@ -0,0 +1,47 @@
+use crate::{av_probe_input_format3, AVInputFormat, AVProbeData, AVPROBE_PADDING_SIZE};
+use std::convert::TryInto;
+use std::ffi::CString;
+
+/// Guess the input format from the leading bytes of the content.
+///
+/// The `filename` is used to match the extension of the formats if provided.
+/// Returns `None` if no format matched.
+pub fn probe_format(data: &[u8], filename: Option<&str>) -> Option<&'static AVInputFormat> {
+    let filename = CString::new(filename.unwrap_or("")).ok()?;
+    // The probe buffer must be followed by zeroed padding bytes.
+    let mut buf = Vec::with_capacity(data.len() + AVPROBE_PADDING_SIZE as usize);
+    buf.extend_from_slice(data);
+    buf.resize(data.len() + AVPROBE_PADDING_SIZE as usize, 0);
+    let mut pd = AVProbeData {
+        filename: filename.as_ptr(),
+        buf: buf.as_mut_ptr(),
+        buf_size: data.len().try_into().ok()?,
+        mime_type: std::ptr::null(),
+    };
+    let mut score = 0;
+    unsafe { av_probe_input_format3(&mut pd, 1, &mut score).as_ref() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn test_probe_format() {
+        let mut mp4 = vec![0x00, 0x00, 0x00, 0x1C];
+        mp4.extend_from_slice(b"ftypisom");
+        mp4.extend_from_slice(&[0x00, 0x00, 0x02, 0x00]);
+        mp4.extend_from_slice(b"isomiso2mp41");
+        mp4.extend_from_slice(&[0x00, 0x00, 0x00, 0x08]);
+        mp4.extend_from_slice(b"free");
+
+        let fmt = probe_format(&mp4, Some("test.mp4")).unwrap();
+        let name = unsafe { CStr::from_ptr(fmt.name).to_string_lossy() };
+        assert!(name.contains("mov") || name.contains("mp4"));
+
+        let fmt = probe_format(&mp4, None).unwrap();
+        let name = unsafe { CStr::from_ptr(fmt.name).to_string_lossy() };
+        assert!(name.contains("mp4"));
+    }
+}
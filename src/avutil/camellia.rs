@@ -0,0 +1,90 @@
+use crate::{
+    av_camellia_alloc, av_camellia_crypt, av_camellia_init, av_free, AvError, AVCAMELLIA, AVERROR,
+};
+use libc::{c_void, EINVAL, ENOMEM};
+
+/// The Camellia block cipher.
+#[derive(Debug)]
+pub struct Camellia {
+    ptr: *mut AVCAMELLIA,
+}
+
+impl Camellia {
+    /// Create a new cipher with the 128, 192 or 256-bit key.
+    pub fn new(key: &[u8]) -> Result<Self, AvError> {
+        if !matches!(key.len(), 16 | 24 | 32) {
+            return Err(AvError(AVERROR(EINVAL)));
+        }
+        let ptr = unsafe { av_camellia_alloc() };
+        if ptr.is_null() {
+            return Err(AvError(AVERROR(ENOMEM)));
+        }
+        let camellia = Self { ptr };
+        let ret = unsafe { av_camellia_init(ptr, key.as_ptr(), key.len() as i32 * 8) };
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(camellia)
+        }
+    }
+
+    /// Encrypt or decrypt the `src` into `dst` by 16 bytes blocks.
+    ///
+    /// The ECB mode is used if `iv` is `None`, otherwise the CBC mode is used
+    /// and the `iv` is updated for the next call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `src` is not a multiple of 16 or `dst` is shorter than `src`.
+    pub fn crypt(&mut self, dst: &mut [u8], src: &[u8], iv: Option<&mut [u8; 16]>, decrypt: bool) {
+        assert_eq!(src.len() % 16, 0, "invalid Camellia data length");
+        assert!(dst.len() >= src.len(), "Camellia output buffer too small");
+        let iv = iv.map_or(std::ptr::null_mut(), |v| v.as_mut_ptr());
+        unsafe {
+            av_camellia_crypt(
+                self.ptr,
+                dst.as_mut_ptr(),
+                src.as_ptr(),
+                (src.len() / 16) as i32,
+                iv,
+                decrypt as i32,
+            )
+        }
+    }
+}
+
+impl Drop for Camellia {
+    fn drop(&mut self) {
+        unsafe { av_free(self.ptr as *mut c_void) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_camellia() {
+        assert_eq!(
+            Camellia::new(&[0; 20]).unwrap_err(),
+            AvError(AVERROR(EINVAL))
+        );
+
+        // RFC 3713, Appendix A, 128-bit key.
+        let data: [u8; 16] = [
+            0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0xfe, 0xdc, 0xba, 0x98, 0x76, 0x54,
+            0x32, 0x10,
+        ];
+        let expected: [u8; 16] = [
+            0x67, 0x67, 0x31, 0x38, 0x54, 0x96, 0x69, 0x73, 0x08, 0x57, 0x06, 0x56, 0x48, 0xea,
+            0xbe, 0x43,
+        ];
+        let mut camellia = Camellia::new(&data).unwrap();
+        let mut ct = [0u8; 16];
+        camellia.crypt(&mut ct, &data, None, false);
+        assert_eq!(ct, expected);
+        let mut pt = [0u8; 16];
+        camellia.crypt(&mut pt, &ct, None, true);
+        assert_eq!(pt, data);
+    }
+}
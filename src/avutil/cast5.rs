@@ -0,0 +1,90 @@
+use crate::{av_cast5_alloc, av_cast5_crypt2, av_cast5_init, av_free, AvError, AVCAST5, AVERROR};
+use libc::{c_void, EINVAL, ENOMEM};
+
+/// The CAST5 (CAST-128) block cipher.
+#[derive(Debug)]
+pub struct Cast5 {
+    ptr: *mut AVCAST5,
+}
+
+impl Cast5 {
+    /// Create a new cipher with the 40 to 128-bit key.
+    pub fn new(key: &[u8]) -> Result<Self, AvError> {
+        if !(5..=16).contains(&key.len()) {
+            return Err(AvError(AVERROR(EINVAL)));
+        }
+        let ptr = unsafe { av_cast5_alloc() };
+        if ptr.is_null() {
+            return Err(AvError(AVERROR(ENOMEM)));
+        }
+        let cast5 = Self { ptr };
+        let ret = unsafe { av_cast5_init(ptr, key.as_ptr(), key.len() as i32 * 8) };
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(cast5)
+        }
+    }
+
+    /// Encrypt or decrypt the `src` into `dst` by 8 bytes blocks.
+    ///
+    /// The ECB mode is used if `iv` is `None`, otherwise the CBC mode is used
+    /// and the `iv` is updated for the next call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `src` is not a multiple of 8 or `dst` is shorter than `src`.
+    pub fn crypt(&mut self, dst: &mut [u8], src: &[u8], iv: Option<&mut [u8; 8]>, decrypt: bool) {
+        assert_eq!(src.len() % 8, 0, "invalid CAST5 data length");
+        assert!(dst.len() >= src.len(), "CAST5 output buffer too small");
+        let iv = iv.map_or(std::ptr::null_mut(), |v| v.as_mut_ptr());
+        unsafe {
+            av_cast5_crypt2(
+                self.ptr,
+                dst.as_mut_ptr(),
+                src.as_ptr(),
+                (src.len() / 8) as i32,
+                iv,
+                decrypt as i32,
+            )
+        }
+    }
+}
+
+impl Drop for Cast5 {
+    fn drop(&mut self) {
+        unsafe { av_free(self.ptr as *mut c_void) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cast5() {
+        assert_eq!(Cast5::new(&[0; 4]).unwrap_err(), AvError(AVERROR(EINVAL)));
+        assert_eq!(Cast5::new(&[0; 17]).unwrap_err(), AvError(AVERROR(EINVAL)));
+
+        // RFC 2144, Appendix B.1, 128-bit key.
+        let key = [
+            0x01, 0x23, 0x45, 0x67, 0x12, 0x34, 0x56, 0x78, 0x23, 0x45, 0x67, 0x89, 0x34, 0x56,
+            0x78, 0x9a,
+        ];
+        let pt = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef];
+        let mut cast5 = Cast5::new(&key).unwrap();
+        let mut ct = [0u8; 8];
+        cast5.crypt(&mut ct, &pt, None, false);
+        assert_eq!(ct, [0x23, 0x8b, 0x4f, 0xe5, 0x84, 0x7e, 0x44, 0xb2]);
+
+        for len in 5..=16 {
+            let mut cast5 = Cast5::new(&key[..len]).unwrap();
+            let src = b"0123456789abcdef";
+            let mut ct = [0u8; 16];
+            cast5.crypt(&mut ct, src, None, false);
+            let mut pt = [0u8; 16];
+            cast5.crypt(&mut pt, &ct, None, true);
+            assert_eq!(&pt, src);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{
+    av_get_channel_description, av_get_channel_layout_nb_channels, av_get_channel_layout_string,
+    av_get_default_channel_layout, av_get_standard_channel_layout,
+};
+use libc::c_char;
+use std::borrow::Cow;
+use std::ffi::CStr;
+
+/// Returns an iterator over the name and mask of all standard channel layouts.
+pub fn standard_layouts() -> impl Iterator<Item = (String, u64)> {
+    let mut index = 0;
+    std::iter::from_fn(move || {
+        let mut layout = 0u64;
+        let mut name = std::ptr::null();
+        let ret = unsafe { av_get_standard_channel_layout(index, &mut layout, &mut name) };
+        if ret < 0 || name.is_null() {
+            return None;
+        }
+        index += 1;
+        let name = unsafe { CStr::from_ptr(name).to_string_lossy().into_owned() };
+        Some((name, layout))
+    })
+}
+
+/// Returns the default channel layout for the number of channels, or 0 if unknown.
+pub fn default_layout(channels: i32) -> u64 {
+    unsafe { av_get_default_channel_layout(channels) as u64 }
+}
+
+/// Alias of `default_layout()` with the FFmpeg naming.
+pub fn get_default_channel_layout(nb: i32) -> u64 {
+    default_layout(nb)
+}
+
+/// Returns the number of channels in the channel layout.
+pub fn av_get_channel_layout_nb_channels_safe(layout: u64) -> i32 {
+    unsafe { av_get_channel_layout_nb_channels(layout) }
+}
+
+/// Returns the name of the channel layout, such as `"stereo"`, or the number
+/// of channels if the `layout` is 0, such as `"2 channels"`.
+pub fn channel_layout_name(nb_channels: i32, layout: u64) -> String {
+    let mut buf = [0 as c_char; 128];
+    unsafe {
+        av_get_channel_layout_string(buf.as_mut_ptr(), buf.len() as i32, nb_channels, layout);
+        CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned()
+    }
+}
+
+/// Returns the description of a single channel, such as `"front left"`.
+pub fn channel_description(channel: u64) -> Option<Cow<'static, str>> {
+    unsafe {
+        let desc = av_get_channel_description(channel);
+        if desc.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(desc).to_string_lossy())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AV_CH_FRONT_LEFT, AV_CH_LAYOUT_5POINT1, AV_CH_LAYOUT_MONO, AV_CH_LAYOUT_STEREO};
+
+    #[test]
+    fn test_channel_layout() {
+        assert_eq!(default_layout(1), AV_CH_LAYOUT_MONO);
+        assert_eq!(default_layout(2), AV_CH_LAYOUT_STEREO);
+        assert_eq!(default_layout(0), 0);
+        assert!(standard_layouts()
+            .any(|(name, layout)| name == "stereo" && layout == AV_CH_LAYOUT_STEREO));
+        assert_eq!(channel_description(AV_CH_FRONT_LEFT).unwrap(), "front left");
+        assert!(channel_description(AV_CH_LAYOUT_STEREO).is_none());
+    }
+
+    #[test]
+    fn test_channel_layout_name() {
+        assert_eq!(
+            av_get_channel_layout_nb_channels_safe(AV_CH_LAYOUT_STEREO),
+            2
+        );
+        assert_eq!(
+            av_get_channel_layout_nb_channels_safe(AV_CH_LAYOUT_5POINT1),
+            6
+        );
+        assert_eq!(get_default_channel_layout(2), AV_CH_LAYOUT_STEREO);
+        assert_eq!(channel_layout_name(2, AV_CH_LAYOUT_STEREO), "stereo");
+        assert_eq!(channel_layout_name(0, AV_CH_LAYOUT_MONO), "mono");
+        assert_eq!(channel_layout_name(3, 0), "3 channels");
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{
+    av_dict_count, av_dict_get, av_dict_get_string, av_dict_parse_string, av_free, AVDictionary,
+    AVDictionaryEntry, AvError, AVERROR, AV_DICT_IGNORE_SUFFIX,
+};
+use libc::{c_char, c_void, EINVAL};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+
+impl AVDictionary {
+    /// Returns an iterator over the `(key, value)` entries in the insertion order.
+    pub fn iter(&self) -> AVDictionaryIter<'_> {
+        AVDictionaryIter {
+            dict: self,
+            entry: std::ptr::null_mut(),
+        }
+    }
+
+    /// Returns the value of the `key`, matched case insensitively.
+    pub fn get(&self, key: &str) -> Option<Cow<'_, str>> {
+        let key = CString::new(key).ok()?;
+        unsafe {
+            let entry = av_dict_get(self, key.as_ptr(), std::ptr::null(), 0);
+            if entry.is_null() {
+                None
+            } else {
+                Some(to_str((*entry).value))
+            }
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        unsafe { av_dict_count(self).max(0) as usize }
+    }
+
+    /// Returns `true` if there is no entry.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns all entries as a `HashMap`.
+    pub fn to_hash_map(&self) -> HashMap<String, String> {
+        self.iter()
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect()
+    }
+}
+
+/// The iterator over the entries of an `AVDictionary`.
+pub struct AVDictionaryIter<'a> {
+    dict: &'a AVDictionary,
+    entry: *mut AVDictionaryEntry,
+}
+
+impl<'a> Iterator for AVDictionaryIter<'a> {
+    type Item = (Cow<'a, str>, Cow<'a, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            self.entry = av_dict_get(
+                self.dict,
+                b"\0".as_ptr() as _,
+                self.entry,
+                AV_DICT_IGNORE_SUFFIX,
+            );
+            if self.entry.is_null() {
+                None
+            } else {
+                Some((to_str((*self.entry).key), to_str((*self.entry).value)))
+            }
+        }
+    }
+}
+
+unsafe fn to_str<'a>(s: *const c_char) -> Cow<'a, str> {
+    if s.is_null() {
+        Cow::Borrowed("")
+    } else {
+        CStr::from_ptr(s).to_string_lossy()
+    }
+}
+
+/// Parse the key/value pairs in the `input` and add them to the `dict`.
+///
+/// The `key_val_sep` and `pairs_sep` are lists of the accepted separators, such
+/// as `"="` and `":"` for `"key1=val1:key2=val2"`.
+pub fn dict_parse_string(
+    dict: &mut *mut AVDictionary,
+    input: &str,
+    key_val_sep: &str,
+    pairs_sep: &str,
+    flags: i32,
+) -> Result<(), AvError> {
+    let to_cstring = |s: &str| CString::new(s).map_err(|_| AvError(AVERROR(EINVAL)));
+    let input = to_cstring(input)?;
+    let key_val_sep = to_cstring(key_val_sep)?;
+    let pairs_sep = to_cstring(pairs_sep)?;
+    let ret = unsafe {
+        av_dict_parse_string(
+            dict,
+            input.as_ptr(),
+            key_val_sep.as_ptr(),
+            pairs_sep.as_ptr(),
+            flags,
+        )
+    };
+    if ret < 0 {
+        Err(AvError(ret))
+    } else {
+        Ok(())
+    }
+}
+
+/// Serialize the entries of the `dict` to a string, such as `"key1=val1:key2=val2"`
+/// for the `b'='` and `b':'` separators.
+pub fn dict_to_string(
+    dict: &AVDictionary,
+    key_val_sep: u8,
+    pairs_sep: u8,
+) -> Result<String, AvError> {
+    let mut buffer: *mut c_char = std::ptr::null_mut();
+    let ret = unsafe {
+        av_dict_get_string(
+            dict,
+            &mut buffer,
+            key_val_sep as c_char,
+            pairs_sep as c_char,
+        )
+    };
+    if ret < 0 {
+        return Err(AvError(ret));
+    }
+    unsafe {
+        let s = CStr::from_ptr(buffer).to_string_lossy().into_owned();
+        av_free(buffer as *mut c_void);
+        Ok(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::av_dict_free;
+
+    #[test]
+    fn test_dict_parse_string() {
+        let mut dict = std::ptr::null_mut();
+        dict_parse_string(&mut dict, "key1=val1:key2=val2", "=", ":", 0).unwrap();
+        let map = unsafe { (*dict).to_hash_map() };
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["key1"], "val1");
+        assert_eq!(map["key2"], "val2");
+        assert!(dict_parse_string(&mut dict, "key3", "=", ":", 0).is_err());
+        unsafe { av_dict_free(&mut dict) };
+    }
+
+    #[test]
+    fn test_dict_to_string() {
+        let mut dict = std::ptr::null_mut();
+        dict_parse_string(&mut dict, "key1=val1:key2=val2", "=", ":", 0).unwrap();
+        let s = unsafe { dict_to_string(&*dict, b';', b',').unwrap() };
+        assert_eq!(s, "key1;val1,key2;val2");
+
+        let mut parsed = std::ptr::null_mut();
+        dict_parse_string(&mut parsed, &s, ";", ",", 0).unwrap();
+        unsafe {
+            assert_eq!((*parsed).to_hash_map(), (*dict).to_hash_map());
+            av_dict_free(&mut parsed);
+            av_dict_free(&mut dict);
+        }
+    }
+
+    #[test]
+    fn test_dict_iter() {
+        use crate::av_dict_set;
+
+        let mut dict = std::ptr::null_mut();
+        unsafe {
+            av_dict_set(
+                &mut dict,
+                b"title\0".as_ptr() as _,
+                b"foo\0".as_ptr() as _,
+                0,
+            );
+            av_dict_set(
+                &mut dict,
+                b"artist\0".as_ptr() as _,
+                b"bar\0".as_ptr() as _,
+                0,
+            );
+            let d = &*dict;
+            let entries: Vec<_> = d.iter().collect();
+            assert_eq!(
+                entries,
+                [
+                    (Cow::from("title"), Cow::from("foo")),
+                    (Cow::from("artist"), Cow::from("bar"))
+                ]
+            );
+            assert_eq!(d.len(), 2);
+            assert!(!d.is_empty());
+            assert_eq!(d.get("artist").unwrap(), "bar");
+            assert_eq!(d.get("TITLE").unwrap(), "foo");
+            assert!(d.get("album").is_none());
+            av_dict_free(&mut dict);
+        }
+    }
+}
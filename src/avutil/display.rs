@@ -0,0 +1,21 @@
+use crate::av_display_rotation_get;
+
+/// Returns the rotation angle in degrees of the display `matrix`, in the range
+/// `[-180.0, 180.0]` counterclockwise, or NaN if the matrix is singular.
+pub fn av_display_rotation_get_safe(matrix: &[i32; 9]) -> f64 {
+    unsafe { av_display_rotation_get(matrix.as_ptr()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::av_display_rotation_set;
+
+    #[test]
+    fn test_display_rotation_get() {
+        let mut matrix = [0i32; 9];
+        unsafe { av_display_rotation_set(matrix.as_mut_ptr(), 90.0) };
+        assert!((av_display_rotation_get_safe(&matrix) - 90.0).abs() < 1e-6);
+        assert!(av_display_rotation_get_safe(&[0; 9]).is_nan());
+    }
+}
@@ -1,4 +1,4 @@
-use libc::{c_char, c_int, size_t};
+use libc::{c_char, c_int, size_t, EAGAIN};
 
 #[inline(always)]
 pub fn AVERROR(e: c_int) -> c_int {
@@ -60,17 +60,137 @@ extern "C" {
     pub fn av_strerror(errnum: c_int, errbuf: *mut c_char, errbuf_size: size_t) -> c_int;
 }
 
+/// Returns the description of the error code `errnum`, or a message with the
+/// number if the code is unknown.
 pub fn av_err2str(errnum: c_int) -> String {
-    unsafe {
-        use crate::AV_ERROR_MAX_STRING_SIZE;
-        let mut buf: [c_char; AV_ERROR_MAX_STRING_SIZE] = [0; AV_ERROR_MAX_STRING_SIZE];
-        if av_strerror(errnum, buf.as_mut_ptr(), buf.len()) == 0 {
-            std::ffi::CStr::from_ptr(buf.as_ptr())
-                .to_str()
-                .unwrap()
-                .to_owned()
+    use crate::AV_ERROR_MAX_STRING_SIZE;
+    let mut buf: [c_char; AV_ERROR_MAX_STRING_SIZE] = [0; AV_ERROR_MAX_STRING_SIZE];
+    let ret = unsafe { av_strerror(errnum, buf.as_mut_ptr(), buf.len()) };
+    if ret < 0 {
+        return format!("Error number {} occurred", errnum);
+    }
+    // The buffer is always terminated by `av_strerror()`, even if truncated.
+    unsafe { std::ffi::CStr::from_ptr(buf.as_ptr()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// The error code returned by the FFmpeg functions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AvError(pub c_int);
+
+impl From<c_int> for AvError {
+    fn from(code: c_int) -> Self {
+        AvError(code)
+    }
+}
+
+impl AvError {
+    /// End of file.
+    pub const EOF: AvError = AvError(AVERROR_EOF);
+
+    /// Resource temporarily unavailable, `AVERROR(EAGAIN)`.
+    pub const AGAIN: AvError = AvError(-EAGAIN);
+
+    /// Returns `Ok(code)` if the `code` is non-negative, the error otherwise.
+    #[inline]
+    pub fn result(code: c_int) -> Result<c_int, AvError> {
+        if code < 0 {
+            Err(AvError(code))
         } else {
-            "".to_owned()
+            Ok(code)
         }
     }
+
+    /// Returns `true` if the end of the file was reached.
+    #[inline]
+    pub fn is_eof(self) -> bool {
+        self.0 == AVERROR_EOF
+    }
+
+    /// Returns `true` if the output is not available in the current state and
+    /// more input must be sent, or the input is not accepted until some output
+    /// is received.
+    #[inline]
+    pub fn is_again(self) -> bool {
+        self.0 == AVERROR(EAGAIN)
+    }
+
+    /// Returns `true` if invalid data was found when processing the input.
+    #[inline]
+    pub fn is_invalid_data(self) -> bool {
+        self.0 == AVERROR_INVALIDDATA
+    }
+
+    /// Returns `true` if a component, such as a decoder, a muxer, a filter or
+    /// an option, was not found.
+    pub fn is_not_found(self) -> bool {
+        matches!(
+            self.0,
+            AVERROR_BSF_NOT_FOUND
+                | AVERROR_DECODER_NOT_FOUND
+                | AVERROR_DEMUXER_NOT_FOUND
+                | AVERROR_ENCODER_NOT_FOUND
+                | AVERROR_FILTER_NOT_FOUND
+                | AVERROR_MUXER_NOT_FOUND
+                | AVERROR_OPTION_NOT_FOUND
+                | AVERROR_PROTOCOL_NOT_FOUND
+                | AVERROR_STREAM_NOT_FOUND
+        )
+    }
+}
+
+impl std::fmt::Display for AvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&av_err2str(self.0))
+    }
+}
+
+impl std::error::Error for AvError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_averror_predicates() {
+        let eof = AvError::from(AVERROR_EOF);
+        assert!(eof.is_eof());
+        assert!(!eof.is_again());
+
+        let again = AvError::from(AVERROR(EAGAIN));
+        assert!(again.is_again());
+        assert!(!again.is_eof());
+
+        let invalid = AvError::from(AVERROR_INVALIDDATA);
+        assert!(invalid.is_invalid_data());
+        assert!(!invalid.is_not_found());
+
+        assert!(AvError::from(AVERROR_DECODER_NOT_FOUND).is_not_found());
+        assert!(AvError::from(AVERROR_OPTION_NOT_FOUND).is_not_found());
+        assert!(!AvError::from(AVERROR_BUG).is_not_found());
+    }
+
+    #[test]
+    fn test_averror_display() {
+        assert!(!AvError::EOF.to_string().is_empty());
+        assert_eq!(AvError::EOF.to_string(), av_err2str(AVERROR_EOF));
+        assert!(AvError::AGAIN.is_again());
+        assert_eq!(AvError::result(3), Ok(3));
+        assert_eq!(AvError::result(0), Ok(0));
+        assert_eq!(AvError::result(AVERROR_EOF), Err(AvError::EOF));
+
+        let err: Box<dyn std::error::Error> = Box::new(AvError(AVERROR_INVALIDDATA));
+        assert!(!err.to_string().is_empty());
+    }
+
+    #[test]
+    fn test_av_err2str() {
+        assert_eq!(av_err2str(AVERROR_EOF), "End of file");
+        assert!(av_err2str(AVERROR(libc::ENOENT)).contains("No such file"));
+        assert_eq!(
+            av_err2str(-0x7fff_0000),
+            "Error number -2147418112 occurred"
+        );
+    }
 }
@@ -0,0 +1,422 @@
+use crate::{
+    av_frame_alloc, av_frame_copy, av_frame_free, av_frame_get_buffer, av_get_bytes_per_sample,
+    av_get_channel_layout_nb_channels, av_pix_fmt_count_planes, av_sample_fmt_is_planar, AVFrame,
+    AVPixelFormat, AVSampleFormat, AvError, AVERROR, AV_NOPTS_VALUE,
+};
+use libc::ENOMEM;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+impl AVFrame {
+    /// Copy the data of the `src` into this frame, both frames must have the
+    /// same format and dimensions, and the buffers must be allocated.
+    pub fn copy_from(&mut self, src: &AVFrame) -> Result<(), AvError> {
+        let ret = unsafe { av_frame_copy(self, src) };
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the bytes of the video `plane`, `None` if the plane is out of
+    /// range, not allocated or stored bottom-up with a negative linesize.
+    pub fn data(&self, plane: usize) -> Option<&[u8]> {
+        let len = self.plane_len(plane)?;
+        unsafe { Some(std::slice::from_raw_parts(self.data[plane], len)) }
+    }
+
+    /// Returns the mutable bytes of the video `plane`, `None` if the plane is
+    /// out of range, not allocated or stored bottom-up with a negative linesize.
+    pub fn data_mut(&mut self, plane: usize) -> Option<&mut [u8]> {
+        let len = self.plane_len(plane)?;
+        unsafe { Some(std::slice::from_raw_parts_mut(self.data[plane], len)) }
+    }
+
+    /// Returns the size in bytes of each line of the `plane`, `None` if the
+    /// plane is out of range.
+    pub fn linesize(&self, plane: usize) -> Option<i32> {
+        if plane < self.planes() {
+            Some(self.linesize[plane])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of planes of the pixel format, 0 if not a video frame.
+    pub fn planes(&self) -> usize {
+        if self.width <= 0 || self.height <= 0 {
+            return 0;
+        }
+        let fmt = match AVPixelFormat::from_raw(self.format) {
+            Some(v) => v,
+            None => return 0,
+        };
+        let ret = unsafe { av_pix_fmt_count_planes(fmt) };
+        if ret < 0 {
+            0
+        } else {
+            ret as usize
+        }
+    }
+
+    /// Returns the number of audio samples per channel.
+    #[inline]
+    pub fn nb_samples(&self) -> usize {
+        self.nb_samples.max(0) as usize
+    }
+
+    /// Returns `true` if the audio samples are stored in one plane per channel.
+    pub fn is_planar(&self) -> bool {
+        match self.sample_format() {
+            Some(fmt) => unsafe { av_sample_fmt_is_planar(fmt) != 0 },
+            None => false,
+        }
+    }
+
+    /// Returns the audio samples of the `channel` for the planar formats, or
+    /// the interleaved samples of all channels for the packed formats, where
+    /// only the `channel` 0 is valid.
+    pub fn audio_data(&self, channel: usize) -> Option<&[u8]> {
+        if self.channels <= 0 {
+            return None;
+        }
+        let bytes_per_sample = unsafe { av_get_bytes_per_sample(self.sample_format()?) };
+        if bytes_per_sample <= 0 {
+            return None;
+        }
+        let mut len = self.nb_samples() * bytes_per_sample as usize;
+        if self.is_planar() {
+            if channel >= self.channels as usize {
+                return None;
+            }
+        } else if channel > 0 {
+            return None;
+        } else {
+            len *= self.channels as usize;
+        }
+        // The planes beyond the `data` array are only in the `extended_data`.
+        let ptr = if !self.extended_data.is_null() {
+            unsafe { *self.extended_data.add(channel) }
+        } else if channel < self.data.len() {
+            self.data[channel]
+        } else {
+            return None;
+        };
+        if ptr.is_null() {
+            None
+        } else {
+            unsafe { Some(std::slice::from_raw_parts(ptr, len)) }
+        }
+    }
+
+    fn sample_format(&self) -> Option<AVSampleFormat> {
+        AVSampleFormat::from_raw(self.format)
+    }
+
+    fn plane_len(&self, plane: usize) -> Option<usize> {
+        let linesize = self.linesize(plane)?;
+        if self.data[plane].is_null() || linesize < 0 {
+            return None;
+        }
+        // The chroma planes are subsampled vertically.
+        let height = if plane == 1 || plane == 2 {
+            let fmt = AVPixelFormat::from_raw(self.format)?;
+            let (_, v_shift) = fmt.chroma_subsampling()?;
+            (self.height + (1 << v_shift) - 1) >> v_shift
+        } else {
+            self.height
+        };
+        Some(linesize as usize * height as usize)
+    }
+}
+
+/// The owned frame which is freed on drop.
+#[derive(Debug)]
+pub struct OwnedFrame {
+    ptr: *mut AVFrame,
+}
+
+impl OwnedFrame {
+    /// Allocate a frame with the default values, the data buffers are not allocated.
+    pub fn new() -> Result<Self, AvError> {
+        let ptr = unsafe { av_frame_alloc() };
+        if ptr.is_null() {
+            Err(AvError(AVERROR(ENOMEM)))
+        } else {
+            Ok(Self { ptr })
+        }
+    }
+
+    /// Set the audio properties of the frame and allocate the data buffers.
+    ///
+    /// The `align` is the buffer size alignment, 0 to let FFmpeg choose.
+    pub fn alloc_audio(
+        &mut self,
+        fmt: AVSampleFormat,
+        channel_layout: u64,
+        nb_samples: i32,
+        sample_rate: i32,
+        align: i32,
+    ) -> Result<(), AvError> {
+        let frame = &mut **self;
+        frame.format = fmt as i32;
+        frame.channel_layout = channel_layout;
+        frame.channels = unsafe { av_get_channel_layout_nb_channels(channel_layout) };
+        frame.nb_samples = nb_samples;
+        frame.sample_rate = sample_rate;
+        self.get_buffer(align)
+    }
+
+    /// Set the video properties of the frame and allocate the data buffers.
+    ///
+    /// The `align` is the buffer size alignment, 0 to let FFmpeg choose.
+    pub fn alloc_video(
+        &mut self,
+        width: i32,
+        height: i32,
+        fmt: AVPixelFormat,
+        align: i32,
+    ) -> Result<(), AvError> {
+        let frame = &mut **self;
+        frame.format = fmt as i32;
+        frame.width = width;
+        frame.height = height;
+        self.get_buffer(align)
+    }
+
+    fn get_buffer(&mut self, align: i32) -> Result<(), AvError> {
+        let ret = unsafe { av_frame_get_buffer(self.ptr, align) };
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the raw pointer of the frame.
+    pub fn as_ptr(&self) -> *const AVFrame {
+        self.ptr
+    }
+
+    /// Returns the mutable raw pointer of the frame.
+    pub fn as_mut_ptr(&mut self) -> *mut AVFrame {
+        self.ptr
+    }
+}
+
+impl Deref for OwnedFrame {
+    type Target = AVFrame;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.ptr }
+    }
+}
+
+impl DerefMut for OwnedFrame {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.ptr }
+    }
+}
+
+impl Drop for OwnedFrame {
+    fn drop(&mut self) {
+        unsafe { av_frame_free(&mut self.ptr) }
+    }
+}
+
+impl fmt::Debug for AVFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("AVFrame");
+        if self.width > 0 && self.height > 0 {
+            let name = AVPixelFormat::from_raw(self.format).map_or("none".into(), |v| v.name());
+            s.field("width", &self.width)
+                .field("height", &self.height)
+                .field("format", &name);
+        } else {
+            let name = AVSampleFormat::from_raw(self.format).map_or("none".into(), |v| v.name());
+            s.field("nb_samples", &self.nb_samples)
+                .field("sample_rate", &self.sample_rate)
+                .field("channels", &self.channels)
+                .field("format", &name);
+        }
+        if self.pts == AV_NOPTS_VALUE {
+            s.field("pts", &format_args!("NOPTS"));
+        } else {
+            s.field("pts", &self.pts);
+        }
+        s.field("key_frame", &(self.key_frame != 0)).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn alloc_video_frame(width: i32, height: i32) -> *mut AVFrame {
+        let frame = av_frame_alloc();
+        (*frame).format = AVPixelFormat::AV_PIX_FMT_YUV420P as i32;
+        (*frame).width = width;
+        (*frame).height = height;
+        assert_eq!(av_frame_get_buffer(frame, 0), 0);
+        frame
+    }
+
+    #[test]
+    fn test_avframe_copy_from() {
+        unsafe {
+            let mut src = alloc_video_frame(16, 16);
+            let mut dst = alloc_video_frame(16, 16);
+            for y in 0..16 {
+                let line = (*src).data[0].offset((y * (*src).linesize[0]) as isize);
+                for x in 0..16 {
+                    *line.offset(x as isize) = (y * 16 + x) as u8;
+                }
+            }
+            (*dst).copy_from(&*src).unwrap();
+            for y in 0..16 {
+                let src_line = std::slice::from_raw_parts(
+                    (*src).data[0].offset((y * (*src).linesize[0]) as isize),
+                    16,
+                );
+                let dst_line = std::slice::from_raw_parts(
+                    (*dst).data[0].offset((y * (*dst).linesize[0]) as isize),
+                    16,
+                );
+                assert_eq!(src_line, dst_line);
+            }
+
+            let mut other = alloc_video_frame(32, 32);
+            assert!((*dst).copy_from(&*other).is_err());
+            av_frame_free(&mut other);
+            av_frame_free(&mut dst);
+            av_frame_free(&mut src);
+        }
+    }
+
+    #[test]
+    fn test_avframe_debug() {
+        unsafe {
+            let mut frame = av_frame_alloc();
+            (*frame).format = AVPixelFormat::AV_PIX_FMT_YUV420P as i32;
+            (*frame).width = 1920;
+            (*frame).height = 1080;
+            (*frame).key_frame = 1;
+            assert_eq!(
+                format!("{:?}", *frame),
+                "AVFrame { width: 1920, height: 1080, format: \"yuv420p\", pts: NOPTS, key_frame: true }"
+            );
+            (*frame).pts = 3000;
+            (*frame).key_frame = 0;
+            assert_eq!(
+                format!("{:?}", *frame),
+                "AVFrame { width: 1920, height: 1080, format: \"yuv420p\", pts: 3000, key_frame: false }"
+            );
+            (*frame).format = i32::MAX;
+            assert!(format!("{:?}", *frame).contains("format: \"none\""));
+            av_frame_free(&mut frame);
+        }
+    }
+
+    #[test]
+    fn test_owned_frame_alloc() {
+        use crate::AV_CH_LAYOUT_STEREO;
+
+        let mut frame = OwnedFrame::new().unwrap();
+        frame
+            .alloc_video(320, 240, AVPixelFormat::AV_PIX_FMT_YUV420P, 0)
+            .unwrap();
+        assert!(!frame.data[0].is_null());
+        assert!(frame.linesize[0] >= 320);
+        assert!(!frame.data[2].is_null());
+
+        let mut frame = OwnedFrame::new().unwrap();
+        frame
+            .alloc_audio(
+                AVSampleFormat::AV_SAMPLE_FMT_FLTP,
+                AV_CH_LAYOUT_STEREO,
+                1024,
+                48000,
+                0,
+            )
+            .unwrap();
+        assert!(!frame.data[0].is_null());
+        assert!(!frame.data[1].is_null());
+        assert_eq!(frame.channels, 2);
+        assert_eq!(frame.sample_rate, 48000);
+
+        let mut frame = OwnedFrame::new().unwrap();
+        assert!(frame
+            .alloc_video(0, 0, AVPixelFormat::AV_PIX_FMT_NONE, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_avframe_data() {
+        let mut y = vec![0u8; 16 * 8];
+        let mut u = vec![0u8; 8 * 4];
+        let mut v = vec![0u8; 8 * 4];
+        let mut frame: AVFrame = unsafe { std::mem::zeroed() };
+        assert_eq!(frame.planes(), 0);
+        assert!(frame.data(0).is_none());
+
+        frame.format = AVPixelFormat::AV_PIX_FMT_YUV420P as i32;
+        frame.width = 16;
+        frame.height = 8;
+        frame.linesize[0] = 16;
+        frame.linesize[1] = 8;
+        frame.linesize[2] = 8;
+        frame.data[0] = y.as_mut_ptr();
+        frame.data[1] = u.as_mut_ptr();
+        assert_eq!(frame.planes(), 3);
+        assert_eq!(frame.linesize(1), Some(8));
+        assert_eq!(frame.linesize(3), None);
+        assert_eq!(frame.data(0).map(|v| v.len()), Some(16 * 8));
+        assert_eq!(frame.data(1).map(|v| v.len()), Some(8 * 4));
+        assert!(frame.data(2).is_none());
+        assert!(frame.data(3).is_none());
+        assert!(frame.data(8).is_none());
+
+        frame.data[2] = v.as_mut_ptr();
+        frame.data_mut(2).unwrap()[31] = 0xff;
+        assert_eq!(v[31], 0xff);
+
+        frame.linesize[0] = -16;
+        assert!(frame.data(0).is_none());
+
+        frame.format = i32::MAX;
+        assert_eq!(frame.planes(), 0);
+        assert!(frame.data(1).is_none());
+    }
+
+    #[test]
+    fn test_avframe_audio_data() {
+        let mut packed = vec![0i16; 2 * 1024];
+        let mut frame: AVFrame = unsafe { std::mem::zeroed() };
+        frame.format = AVSampleFormat::AV_SAMPLE_FMT_S16 as i32;
+        frame.channels = 2;
+        frame.nb_samples = 1024;
+        frame.data[0] = packed.as_mut_ptr() as *mut u8;
+        assert!(!frame.is_planar());
+        assert_eq!(frame.nb_samples(), 1024);
+        assert_eq!(frame.audio_data(0).map(|v| v.len()), Some(2 * 1024 * 2));
+        assert!(frame.audio_data(1).is_none());
+
+        let mut left = vec![0f32; 1024];
+        let mut right = vec![0f32; 1024];
+        let mut frame: AVFrame = unsafe { std::mem::zeroed() };
+        frame.format = AVSampleFormat::AV_SAMPLE_FMT_FLTP as i32;
+        frame.channels = 2;
+        frame.nb_samples = 1024;
+        frame.data[0] = left.as_mut_ptr() as *mut u8;
+        frame.data[1] = right.as_mut_ptr() as *mut u8;
+        assert!(frame.is_planar());
+        assert_eq!(frame.audio_data(0).map(|v| v.len()), Some(1024 * 4));
+        assert_eq!(frame.audio_data(1).map(|v| v.len()), Some(1024 * 4));
+        assert!(frame.audio_data(2).is_none());
+
+        frame.format = i32::MAX;
+        assert!(!frame.is_planar());
+        assert!(frame.audio_data(0).is_none());
+    }
+}
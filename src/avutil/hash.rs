@@ -0,0 +1,79 @@
+use crate::{
+    av_free, av_md5_alloc, av_md5_final, av_md5_init, av_md5_update, av_ripemd_alloc,
+    av_ripemd_final, av_ripemd_init, av_ripemd_update, AvError, AVERROR,
+};
+use libc::{c_void, EINVAL, ENOMEM};
+
+/// Calculate the MD5 hash of the data.
+pub fn md5(data: &[u8]) -> Result<[u8; 16], AvError> {
+    unsafe {
+        let ctx = av_md5_alloc();
+        if ctx.is_null() {
+            return Err(AvError(AVERROR(ENOMEM)));
+        }
+        av_md5_init(ctx);
+        for chunk in data.chunks(i32::MAX as usize) {
+            av_md5_update(ctx, chunk.as_ptr(), chunk.len() as _);
+        }
+        let mut digest = [0u8; 16];
+        av_md5_final(ctx, digest.as_mut_ptr());
+        av_free(ctx as *mut c_void);
+        Ok(digest)
+    }
+}
+
+/// Calculate the RIPEMD hash of the data, the `bits` is one of 128, 160, 256 or 320.
+pub fn ripemd(data: &[u8], bits: u16) -> Result<Vec<u8>, AvError> {
+    if !matches!(bits, 128 | 160 | 256 | 320) {
+        return Err(AvError(AVERROR(EINVAL)));
+    }
+    unsafe {
+        let ctx = av_ripemd_alloc();
+        if ctx.is_null() {
+            return Err(AvError(AVERROR(ENOMEM)));
+        }
+        let ret = av_ripemd_init(ctx, bits as i32);
+        if ret < 0 {
+            av_free(ctx as *mut c_void);
+            return Err(AvError(ret));
+        }
+        for chunk in data.chunks(i32::MAX as usize) {
+            av_ripemd_update(ctx, chunk.as_ptr(), chunk.len() as _);
+        }
+        let mut digest = vec![0u8; bits as usize / 8];
+        av_ripemd_final(ctx, digest.as_mut_ptr());
+        av_free(ctx as *mut c_void);
+        Ok(digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_md5() {
+        assert_eq!(
+            md5(b"abc").unwrap(),
+            [
+                0x90, 0x01, 0x50, 0x98, 0x3c, 0xd2, 0x4f, 0xb0, 0xd6, 0x96, 0x3f, 0x7d, 0x28, 0xe1,
+                0x7f, 0x72
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ripemd() {
+        assert_eq!(ripemd(b"abc", 100), Err(AvError(AVERROR(EINVAL))));
+        assert_eq!(
+            ripemd(b"abc", 160).unwrap(),
+            [
+                0x8e, 0xb2, 0x08, 0xf7, 0xe0, 0x5d, 0x98, 0x7a, 0x9b, 0x04, 0x4a, 0x8e, 0x98, 0xc6,
+                0xb0, 0x87, 0xf1, 0x5a, 0x0b, 0xfc
+            ]
+        );
+        assert_eq!(ripemd(b"abc", 128).unwrap().len(), 16);
+        assert_eq!(ripemd(b"abc", 256).unwrap().len(), 32);
+        assert_eq!(ripemd(b"abc", 320).unwrap().len(), 40);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{
+    av_hash_alloc, av_hash_final, av_hash_freep, av_hash_get_name, av_hash_get_size, av_hash_init,
+    av_hash_names, av_hash_update, AVHashContext, AvError, AVERROR,
+};
+use libc::EINVAL;
+use std::borrow::Cow;
+use std::ffi::{CStr, CString};
+
+/// A generic hasher which supports all hash algorithms provided by FFmpeg.
+#[derive(Debug)]
+pub struct Hasher {
+    ptr: *mut AVHashContext,
+}
+
+impl Hasher {
+    /// Create a new hasher by the algorithm name, such as `"MD5"` or `"SHA256"`.
+    pub fn new(name: &str) -> Result<Self, AvError> {
+        let name = CString::new(name).map_err(|_| AvError(AVERROR(EINVAL)))?;
+        let mut ptr = std::ptr::null_mut();
+        let ret = unsafe { av_hash_alloc(&mut ptr, name.as_ptr()) };
+        if ret < 0 {
+            return Err(AvError(ret));
+        }
+        unsafe { av_hash_init(ptr) };
+        Ok(Self { ptr })
+    }
+
+    /// Returns the names of all available algorithms.
+    pub fn algorithms() -> Vec<Cow<'static, str>> {
+        (0..)
+            .map(|i| unsafe { av_hash_names(i) })
+            .take_while(|name| !name.is_null())
+            .map(|name| unsafe { CStr::from_ptr(name).to_string_lossy() })
+            .collect()
+    }
+
+    /// Returns the name of the algorithm.
+    pub fn name(&self) -> Cow<'static, str> {
+        unsafe { CStr::from_ptr(av_hash_get_name(self.ptr)).to_string_lossy() }
+    }
+
+    /// Returns the size of the digest in bytes.
+    pub fn size(&self) -> usize {
+        unsafe { av_hash_get_size(self.ptr) as usize }
+    }
+
+    /// Update the hash value with the data.
+    pub fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(i32::MAX as usize) {
+            unsafe { av_hash_update(self.ptr, chunk.as_ptr(), chunk.len() as _) }
+        }
+    }
+
+    /// Returns the digest and resets the hasher for the next data.
+    pub fn finish(&mut self) -> Vec<u8> {
+        let mut digest = vec![0u8; self.size()];
+        unsafe {
+            av_hash_final(self.ptr, digest.as_mut_ptr());
+            av_hash_init(self.ptr);
+        }
+        digest
+    }
+}
+
+impl Drop for Hasher {
+    fn drop(&mut self) {
+        unsafe { av_hash_freep(&mut self.ptr) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::md5;
+
+    #[test]
+    fn test_hasher() {
+        let algorithms = Hasher::algorithms();
+        assert!(algorithms.iter().any(|v| v == "MD5"));
+        assert!(algorithms.iter().any(|v| v == "SHA256"));
+        assert!(Hasher::new("NO-SUCH-HASH").is_err());
+
+        let mut hasher = Hasher::new("MD5").unwrap();
+        assert_eq!(hasher.name(), "MD5");
+        assert_eq!(hasher.size(), 16);
+        hasher.update(b"The quick brown fox ");
+        hasher.update(b"jumps over the lazy dog");
+        assert_eq!(
+            hasher.finish(),
+            md5(b"The quick brown fox jumps over the lazy dog").unwrap()
+        );
+        hasher.update(b"abc");
+        assert_eq!(hasher.finish(), md5(b"abc").unwrap());
+
+        assert_eq!(Hasher::new("SHA256").unwrap().finish().len(), 32);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    av_image_copy, av_image_fill_arrays, av_image_fill_linesizes, av_image_get_buffer_size,
+    av_image_get_linesize, AVPixelFormat, AvError, AVERROR,
+};
+use libc::EINVAL;
+
+/// Returns the size in bytes of the `plane` for an image with the format and width.
+pub fn image_linesize(fmt: AVPixelFormat, width: i32, plane: i32) -> Result<i32, AvError> {
+    let ret = unsafe { av_image_get_linesize(fmt, width, plane) };
+    if ret < 0 {
+        Err(AvError(ret))
+    } else {
+        Ok(ret)
+    }
+}
+
+/// Returns the line sizes of all planes for an image with the format and width.
+pub fn fill_linesizes(fmt: AVPixelFormat, width: i32) -> Result<[i32; 4], AvError> {
+    let mut linesizes = [0i32; 4];
+    let ret = unsafe { av_image_fill_linesizes(linesizes.as_mut_ptr(), fmt, width) };
+    if ret < 0 {
+        Err(AvError(ret))
+    } else {
+        Ok(linesizes)
+    }
+}
+
+/// Copy the image planes from `src_data` to `dst_data`, each plane is copied
+/// with its own line size.
+///
+/// All of the data and line size slices must contain 4 entries, the unused
+/// planes are ignored.
+///
+/// # Panics
+///
+/// Panics if any slice contains less than 4 entries.
+///
+/// # Safety
+///
+/// Each used plane of `src_data` must be valid for reads and each used plane
+/// of `dst_data` must be valid for writes of its line size times the plane
+/// height of the image with the format and the size `w` x `h`, and the
+/// planes must not overlap.
+pub unsafe fn image_copy(
+    dst_data: &mut [*mut u8],
+    dst_linesize: &[i32],
+    src_data: &[*const u8],
+    src_linesize: &[i32],
+    fmt: AVPixelFormat,
+    w: i32,
+    h: i32,
+) {
+    assert!(
+        dst_data.len() >= 4 && dst_linesize.len() >= 4,
+        "invalid destination planes"
+    );
+    assert!(
+        src_data.len() >= 4 && src_linesize.len() >= 4,
+        "invalid source planes"
+    );
+    av_image_copy(
+        dst_data.as_mut_ptr(),
+        dst_linesize.as_ptr(),
+        src_data.as_ptr() as *mut *const u8,
+        src_linesize.as_ptr(),
+        fmt,
+        w,
+        h,
+    )
+}
+
+/// Setup the plane pointers and line sizes for the image in the `src` buffer,
+/// returns the size in bytes required for the image.
+///
+/// The plane pointers point into the `src`, they are valid for reads and
+/// writes as long as the `src` is not moved or dropped.
+/// Returns `EINVAL` if the `src` is too small for the image.
+pub fn image_fill_arrays(
+    dst_data: &mut [*mut u8; 4],
+    dst_linesize: &mut [i32; 4],
+    src: &mut [u8],
+    fmt: AVPixelFormat,
+    w: i32,
+    h: i32,
+    align: i32,
+) -> Result<i32, AvError> {
+    let size = unsafe { av_image_get_buffer_size(fmt, w, h, align) };
+    if size < 0 {
+        return Err(AvError(size));
+    }
+    if src.len() < size as usize {
+        return Err(AvError(AVERROR(EINVAL)));
+    }
+    let ret = unsafe {
+        av_image_fill_arrays(
+            dst_data.as_mut_ptr(),
+            dst_linesize.as_mut_ptr(),
+            src.as_mut_ptr(),
+            fmt,
+            w,
+            h,
+            align,
+        )
+    };
+    if ret < 0 {
+        Err(AvError(ret))
+    } else {
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AVPixelFormat::*;
+
+    #[test]
+    fn test_linesizes() {
+        assert_eq!(image_linesize(AV_PIX_FMT_YUV420P, 1920, 0), Ok(1920));
+        assert_eq!(image_linesize(AV_PIX_FMT_YUV420P, 1920, 1), Ok(960));
+        assert_eq!(image_linesize(AV_PIX_FMT_YUV420P, 1920, 2), Ok(960));
+        assert!(image_linesize(AV_PIX_FMT_YUV420P, 1920, 4).is_err());
+        assert!(image_linesize(AV_PIX_FMT_NONE, 1920, 0).is_err());
+        assert_eq!(
+            fill_linesizes(AV_PIX_FMT_YUV420P, 1920),
+            Ok([1920, 960, 960, 0])
+        );
+        assert_eq!(fill_linesizes(AV_PIX_FMT_RGB24, 100), Ok([300, 0, 0, 0]));
+        assert!(fill_linesizes(AV_PIX_FMT_YUV420P, -1).is_err());
+    }
+
+    #[test]
+    fn test_image_copy() {
+        // A 4x4 yuv420p image with padded source lines.
+        let (w, h) = (4, 4);
+        let src_linesize = [8, 4, 4, 0];
+        let src_y: Vec<u8> = (0..32).collect();
+        let src_u: Vec<u8> = (100..108).collect();
+        let src_v: Vec<u8> = (200..208).collect();
+        let src_data = [
+            src_y.as_ptr(),
+            src_u.as_ptr(),
+            src_v.as_ptr(),
+            std::ptr::null(),
+        ];
+
+        let dst_linesize = fill_linesizes(AVPixelFormat::AV_PIX_FMT_YUV420P, w).unwrap();
+        assert_eq!(dst_linesize, [4, 2, 2, 0]);
+        let mut dst_y = vec![0u8; 16];
+        let mut dst_u = vec![0u8; 4];
+        let mut dst_v = vec![0u8; 4];
+        let mut dst_data = [
+            dst_y.as_mut_ptr(),
+            dst_u.as_mut_ptr(),
+            dst_v.as_mut_ptr(),
+            std::ptr::null_mut(),
+        ];
+
+        unsafe {
+            image_copy(
+                &mut dst_data,
+                &dst_linesize,
+                &src_data,
+                &src_linesize,
+                AVPixelFormat::AV_PIX_FMT_YUV420P,
+                w,
+                h,
+            )
+        };
+        for y in 0..4 {
+            assert_eq!(dst_y[y * 4..y * 4 + 4], src_y[y * 8..y * 8 + 4]);
+        }
+        for y in 0..2 {
+            assert_eq!(dst_u[y * 2..y * 2 + 2], src_u[y * 4..y * 4 + 2]);
+            assert_eq!(dst_v[y * 2..y * 2 + 2], src_v[y * 4..y * 4 + 2]);
+        }
+    }
+
+    #[test]
+    fn test_image_fill_arrays() {
+        let mut buf = vec![0u8; 100 * 20 * 3];
+        let mut data = [std::ptr::null_mut(); 4];
+        let mut linesize = [0; 4];
+        let size = image_fill_arrays(
+            &mut data,
+            &mut linesize,
+            &mut buf,
+            AVPixelFormat::AV_PIX_FMT_RGB24,
+            100,
+            20,
+            1,
+        )
+        .unwrap();
+        assert_eq!(size, 6000);
+        assert_eq!(linesize, [300, 0, 0, 0]);
+        assert_eq!(data[0], buf.as_mut_ptr());
+        assert!(data[1].is_null());
+
+        assert_eq!(
+            image_fill_arrays(
+                &mut data,
+                &mut linesize,
+                &mut buf[..5999],
+                AVPixelFormat::AV_PIX_FMT_RGB24,
+                100,
+                20,
+                1,
+            ),
+            Err(AvError(AVERROR(EINVAL)))
+        );
+    }
+}
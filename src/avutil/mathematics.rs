@@ -1,4 +1,7 @@
-use crate::AVRounding;
+use crate::{
+    av_find_nearest_q_idx, av_nearer_q, av_q2intfloat, av_rescale, av_rescale_delta, av_rescale_q,
+    av_rescale_q_rnd, AVRational, AVRounding,
+};
 
 impl From<AVRounding> for u32 {
     fn from(v: AVRounding) -> u32 {
@@ -56,6 +59,117 @@ impl AVRounding {
     pub fn pass_min_max(self) -> Self {
         unsafe { std::mem::transmute::<u32, AVRounding>(self as u32 | 8192) }
     }
+
+    /// Clear the flag of pass INT64_MIN/MAX through unchanged.
+    #[inline]
+    pub fn without_pass_min_max(self) -> Self {
+        unsafe { std::mem::transmute::<u32, AVRounding>(self as u32 & !8192) }
+    }
+
+    /// Returns the rounding mode without the flag of pass INT64_MIN/MAX.
+    #[inline]
+    pub fn mode(self) -> Self {
+        self.without_pass_min_max()
+    }
+
+    /// Returns `true` if the flag of pass INT64_MIN/MAX is set.
+    #[inline]
+    pub fn has_pass_min_max(self) -> bool {
+        self as u32 & 8192 != 0
+    }
+}
+
+/// Rescale the integer with the rounding to nearest, returns `a * b / c`
+/// without overflowing the intermediate result.
+pub fn rescale(a: i64, b: i64, c: i64) -> i64 {
+    unsafe { av_rescale(a, b, c) }
+}
+
+/// Rescale the timestamp `ts` from the time base `from` to `to`, with the
+/// rounding to nearest.
+///
+/// The `AV_NOPTS_VALUE` is not preserved, use `rescale_q_rnd()` with the
+/// `pass_min_max()` rounding for the timestamps which may be unknown.
+pub fn rescale_q(ts: i64, from: AVRational, to: AVRational) -> i64 {
+    unsafe { av_rescale_q(ts, from, to) }
+}
+
+/// Rescale the timestamp `ts` from the time base `from` to `to` with the
+/// rounding `rnd`.
+///
+/// If the `rnd` has `pass_min_max()`, the `AV_NOPTS_VALUE` (`i64::MIN`) and
+/// `i64::MAX` are passed through unchanged.
+pub fn rescale_q_rnd(ts: i64, from: AVRational, to: AVRational, rnd: AVRounding) -> i64 {
+    unsafe { av_rescale_q_rnd(ts, from, to, rnd) }
+}
+
+/// Rescale a timestamp while preserving the known durations.
+///
+/// The `fs_tb` is a timebase finer than or equal to the `in_tb`, usually the
+/// sample rate, and `duration` is the duration of the current frame in it.
+///
+/// The `last` accumulates the expected timestamp of the next frame in `fs_tb`,
+/// it must be initialized to `AV_NOPTS_VALUE` and passed to every call for the
+/// same stream. As long as `in_ts` stays close to it, the result is derived
+/// from it instead of `in_ts`, so the rounding errors won't accumulate.
+pub fn rescale_delta(
+    in_tb: AVRational,
+    in_ts: i64,
+    fs_tb: AVRational,
+    duration: i32,
+    last: &mut i64,
+    out_tb: AVRational,
+) -> i64 {
+    unsafe { av_rescale_delta(in_tb, in_ts, fs_tb, duration, last, out_tb) }
+}
+
+/// Convert the rational to the bit pattern of the IEEE 32-bit float.
+pub fn q2intfloat(q: AVRational) -> u32 {
+    unsafe { av_q2intfloat(q) }
+}
+
+/// Reinterpret the bit pattern as an IEEE 32-bit float, like `av_int2float()`.
+#[inline]
+pub fn int2float(v: u32) -> f32 {
+    f32::from_bits(v)
+}
+
+/// Reinterpret the IEEE 32-bit float as a bit pattern, like `av_float2int()`.
+#[inline]
+pub fn float2int(v: f32) -> u32 {
+    v.to_bits()
+}
+
+/// Reinterpret the bit pattern as an IEEE 64-bit float, like `av_int2double()`.
+#[inline]
+pub fn int2double(v: u64) -> f64 {
+    f64::from_bits(v)
+}
+
+/// Reinterpret the IEEE 64-bit float as a bit pattern, like `av_double2int()`.
+#[inline]
+pub fn double2int(v: f64) -> u64 {
+    v.to_bits()
+}
+
+/// Returns 1 if `q1` is nearer to `q` than `q2`, -1 if `q2` is nearer than
+/// `q1`, 0 if they have the same distance.
+pub fn nearer_q(q: AVRational, q1: AVRational, q2: AVRational) -> i32 {
+    unsafe { av_nearer_q(q, q1, q2) }
+}
+
+/// Returns the index of the element in the `list` nearest to `q`, such as for
+/// choosing from the supported frame rates of a codec.
+///
+/// The `list` must not contain `0/0`, which terminates the list in C, 0 is
+/// returned for an empty list.
+pub fn find_nearest_q_idx(q: AVRational, list: &[AVRational]) -> usize {
+    if list.is_empty() {
+        return 0;
+    }
+    let mut q_list = list.to_vec();
+    q_list.push(AVRational { num: 0, den: 0 });
+    unsafe { av_find_nearest_q_idx(q, q_list.as_ptr()) as usize }
 }
 
 #[cfg(test)]
@@ -79,5 +193,104 @@ mod tests {
             AVRounding::AV_ROUND_PASS_MINMAX
         );
         assert_eq!(AVRounding::new().near_inf().pass_min_max() as u32, 8197);
+        assert_eq!(
+            AVRounding::new()
+                .near_inf()
+                .pass_min_max()
+                .without_pass_min_max(),
+            AVRounding::AV_ROUND_NEAR_INF
+        );
+        assert_eq!(
+            AVRounding::new().up().pass_min_max().mode(),
+            AVRounding::AV_ROUND_UP
+        );
+        assert_eq!(AVRounding::new().down().mode(), AVRounding::AV_ROUND_DOWN);
+        assert!(AVRounding::new()
+            .near_inf()
+            .pass_min_max()
+            .has_pass_min_max());
+        assert!(!AVRounding::new().near_inf().has_pass_min_max());
+        assert!(!AVRounding::new()
+            .pass_min_max()
+            .without_pass_min_max()
+            .has_pass_min_max());
+    }
+
+    #[test]
+    fn test_rescale_delta() {
+        use crate::{av_rescale_q, AV_NOPTS_VALUE};
+
+        let ms_tb = AVRational { num: 1, den: 1000 };
+        let fs_tb = AVRational { num: 1, den: 44100 };
+        let mut last = AV_NOPTS_VALUE;
+        let mut drifted = false;
+        for n in 0..1000i64 {
+            // Timestamps of 1024 samples frames rounded to milliseconds.
+            let in_ts = unsafe { av_rescale_q(n * 1024, fs_tb, ms_tb) };
+            let out_ts = rescale_delta(ms_tb, in_ts, fs_tb, 1024, &mut last, fs_tb);
+            assert_eq!(out_ts, n * 1024);
+            assert_eq!(last, (n + 1) * 1024);
+            drifted |= unsafe { av_rescale_q(in_ts, ms_tb, fs_tb) } != n * 1024;
+        }
+        assert!(drifted);
+    }
+
+    #[test]
+    fn test_q2intfloat() {
+        let bits = q2intfloat(AVRational { num: 1, den: 2 });
+        assert_eq!(bits, 0x3f00_0000);
+        assert_eq!(int2float(bits), 0.5);
+        assert_eq!(float2int(0.5), bits);
+        assert_eq!(q2intfloat(AVRational { num: -3, den: 4 }), float2int(-0.75));
+        assert_eq!(int2double(double2int(0.1)), 0.1);
+    }
+
+    #[test]
+    fn test_find_nearest_q() {
+        let ntsc = AVRational {
+            num: 2997,
+            den: 100,
+        };
+        let list = [
+            AVRational { num: 24, den: 1 },
+            AVRational { num: 25, den: 1 },
+            AVRational {
+                num: 30000,
+                den: 1001,
+            },
+            AVRational { num: 60, den: 1 },
+        ];
+        assert_eq!(find_nearest_q_idx(ntsc, &list), 2);
+        assert_eq!(find_nearest_q_idx(ntsc, &list[..2]), 1);
+        assert_eq!(find_nearest_q_idx(ntsc, &[]), 0);
+
+        assert_eq!(nearer_q(ntsc, list[2], list[1]), 1);
+        assert_eq!(nearer_q(ntsc, list[1], list[2]), -1);
+        assert_eq!(nearer_q(ntsc, list[0], list[0]), 0);
+    }
+
+    #[test]
+    fn test_rescale_q() {
+        use crate::AV_NOPTS_VALUE;
+
+        let ms_tb = AVRational { num: 1, den: 1000 };
+        let tb_90k = AVRational { num: 1, den: 90000 };
+        assert_eq!(rescale(3, 90000, 1000), 270);
+        assert_eq!(rescale_q(1500, ms_tb, tb_90k), 135000);
+        assert_eq!(rescale_q(135000, tb_90k, ms_tb), 1500);
+        assert_eq!(rescale_q(44, tb_90k, ms_tb), 0);
+        assert_eq!(rescale_q(46, tb_90k, ms_tb), 1);
+
+        let rnd = AVRounding::new().near_inf().pass_min_max();
+        assert_eq!(rescale_q_rnd(1500, ms_tb, tb_90k, rnd), 135000);
+        assert_eq!(
+            rescale_q_rnd(AV_NOPTS_VALUE, ms_tb, tb_90k, rnd),
+            AV_NOPTS_VALUE
+        );
+        assert_eq!(rescale_q_rnd(89, tb_90k, ms_tb, AVRounding::new().up()), 1);
+        assert_eq!(
+            rescale_q_rnd(89, tb_90k, ms_tb, AVRounding::new().down()),
+            0
+        );
     }
 }
@@ -0,0 +1,75 @@
+use crate::{av_fast_realloc, av_free, av_malloc};
+use libc::c_void;
+
+/// Reallocate the buffer `ptr` if `*size` is smaller than `min_size`, the
+/// buffer is grown with some extra space to amortize the reallocations.
+///
+/// On success, `*size` is updated to the new size, otherwise null is
+/// returned, `*size` is set to 0 and the `ptr` remains valid.
+///
+/// The returned memory must be freed with `av_free()`.
+///
+/// # Safety
+///
+/// The `ptr` must be null or allocated by `av_malloc()` and friends, and
+/// `*size` must be the size of it.
+pub unsafe fn fast_realloc(ptr: *mut c_void, size: &mut u32, min_size: usize) -> *mut c_void {
+    av_fast_realloc(ptr, size, min_size)
+}
+
+/// Allocate an uninitialized array of `count` elements of `T` aligned for
+/// SIMD, returns null if failed or overflowed.
+///
+/// The returned memory must be freed with `typed_free()` or `av_free()`.
+pub fn typed_malloc<T>(count: usize) -> *mut T {
+    match std::mem::size_of::<T>().checked_mul(count) {
+        Some(size) => unsafe { av_malloc(size) as *mut T },
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Free the memory allocated by `typed_malloc()`.
+///
+/// # Safety
+///
+/// The `ptr` must be null or allocated by `av_malloc()` and friends.
+pub unsafe fn typed_free<T>(ptr: *mut T) {
+    av_free(ptr as *mut c_void)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fast_realloc() {
+        unsafe {
+            let mut size = 0u32;
+            let mut ptr = fast_realloc(std::ptr::null_mut(), &mut size, 100);
+            assert!(!ptr.is_null());
+            assert!(size >= 100);
+
+            let mut prev = size;
+            for &min_size in [50, 200, 150, 1000, 10].iter() {
+                ptr = fast_realloc(ptr, &mut size, min_size);
+                assert!(!ptr.is_null());
+                assert!(size >= prev);
+                assert!(size as usize >= min_size);
+                prev = size;
+            }
+            av_free(ptr);
+        }
+    }
+
+    #[test]
+    fn test_typed_malloc() {
+        let ptr = typed_malloc::<u32>(16);
+        assert!(!ptr.is_null());
+        unsafe {
+            std::slice::from_raw_parts_mut(ptr, 16).fill(7);
+            assert_eq!(*ptr.add(15), 7);
+            typed_free(ptr);
+        }
+        assert!(typed_malloc::<u64>(usize::MAX).is_null());
+    }
+}
@@ -18,3 +18,50 @@ pub use self::pixfmt::*;
 
 mod timestamp;
 pub use self::timestamp::*;
+
+mod xtea;
+pub use self::xtea::*;
+
+mod twofish;
+pub use self::twofish::*;
+
+mod camellia;
+pub use self::camellia::*;
+
+mod cast5;
+pub use self::cast5::*;
+
+mod hash;
+pub use self::hash::*;
+
+mod hashctx;
+pub use self::hashctx::*;
+
+#[cfg(any(
+    feature = "ff_api_old_channel_layout",
+    not(feature = "ff_api_old_channel_layout_is_defined")
+))]
+mod channel_layout;
+#[cfg(any(
+    feature = "ff_api_old_channel_layout",
+    not(feature = "ff_api_old_channel_layout_is_defined")
+))]
+pub use self::channel_layout::*;
+
+mod imgutils;
+pub use self::imgutils::*;
+
+mod dict;
+pub use self::dict::*;
+
+mod display;
+pub use self::display::*;
+
+mod frame;
+pub use self::frame::*;
+
+mod samplefmt;
+pub use self::samplefmt::*;
+
+mod mem;
+pub use self::mem::*;
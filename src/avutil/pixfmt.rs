@@ -1,5 +1,7 @@
-use crate::AVPixelFormat;
 use crate::AVPixelFormat::*;
+use crate::{AVPixFmtDescriptor, AVPixelFormat};
+use std::borrow::Cow;
+use std::ffi::{CStr, CString};
 
 #[cfg(target_endian = "little")]
 pub const AV_PIX_FMT_RGB32: AVPixelFormat = AV_PIX_FMT_BGRA;
@@ -233,3 +235,104 @@ pub const AV_PIX_FMT_XYZ12: AVPixelFormat = AV_PIX_FMT_XYZ12BE;
 pub const AV_PIX_FMT_NV20: AVPixelFormat = AV_PIX_FMT_NV20BE;
 #[cfg(target_endian = "big")]
 pub const AV_PIX_FMT_AYUV64: AVPixelFormat = AV_PIX_FMT_AYUV64BE;
+
+impl AVPixelFormat {
+    /// Returns the pixel format of the raw value, such as `AVFrame.format`,
+    /// `None` if out of range.
+    pub fn from_raw(v: i32) -> Option<AVPixelFormat> {
+        if v >= AV_PIX_FMT_NONE as i32 && v < AV_PIX_FMT_NB as i32 {
+            Some(unsafe { std::mem::transmute::<i32, AVPixelFormat>(v) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the `(log2_w, log2_h)` chroma subsampling factors, `None` if unknown.
+    pub fn chroma_subsampling(self) -> Option<(i32, i32)> {
+        let (mut h_shift, mut v_shift) = (0, 0);
+        let ret =
+            unsafe { crate::av_pix_fmt_get_chroma_sub_sample(self, &mut h_shift, &mut v_shift) };
+        if ret < 0 {
+            None
+        } else {
+            Some((h_shift, v_shift))
+        }
+    }
+
+    /// Returns the descriptor of the pixel format, `None` if unknown.
+    pub fn descriptor(self) -> Option<&'static AVPixFmtDescriptor> {
+        unsafe { crate::av_pix_fmt_desc_get(self).as_ref() }
+    }
+
+    /// Returns the short name of the pixel format, `"none"` if unknown.
+    pub fn name(self) -> Cow<'static, str> {
+        unsafe {
+            let name = crate::av_get_pix_fmt_name(self);
+            if name.is_null() {
+                Cow::Borrowed("none")
+            } else {
+                CStr::from_ptr(name).to_string_lossy()
+            }
+        }
+    }
+
+    /// Returns the pixel format of the `name`, `AV_PIX_FMT_NONE` if not found.
+    pub fn from_name(name: &str) -> AVPixelFormat {
+        match CString::new(name) {
+            Ok(name) => unsafe { crate::av_get_pix_fmt(name.as_ptr()) },
+            Err(_) => AV_PIX_FMT_NONE,
+        }
+    }
+
+    /// Returns the number of bits per pixel used by the pixel format,
+    /// 0 if unknown.
+    pub fn bits_per_pixel(self) -> i32 {
+        self.descriptor()
+            .map(|desc| unsafe { crate::av_get_bits_per_pixel(desc) })
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chroma_subsampling() {
+        assert_eq!(AV_PIX_FMT_YUV420P.chroma_subsampling(), Some((1, 1)));
+        assert_eq!(AV_PIX_FMT_YUV444P.chroma_subsampling(), Some((0, 0)));
+        assert_eq!(AV_PIX_FMT_YUV422P.chroma_subsampling(), Some((1, 0)));
+        assert_eq!(AV_PIX_FMT_NONE.chroma_subsampling(), None);
+    }
+
+    #[test]
+    fn test_pix_fmt_from_raw() {
+        assert_eq!(AVPixelFormat::from_raw(-1), Some(AV_PIX_FMT_NONE));
+        assert_eq!(AVPixelFormat::from_raw(0), Some(AV_PIX_FMT_YUV420P));
+        assert_eq!(
+            AVPixelFormat::from_raw(AV_PIX_FMT_NV12 as i32),
+            Some(AV_PIX_FMT_NV12)
+        );
+        assert_eq!(AVPixelFormat::from_raw(AV_PIX_FMT_NB as i32), None);
+        assert_eq!(AVPixelFormat::from_raw(-2), None);
+        assert_eq!(AVPixelFormat::from_raw(i32::MAX), None);
+    }
+
+    #[test]
+    fn test_pix_fmt_descriptor() {
+        assert_eq!(AV_PIX_FMT_YUV420P.name(), "yuv420p");
+        assert_eq!(AVPixelFormat::from_name("yuv420p"), AV_PIX_FMT_YUV420P);
+        assert_eq!(
+            AVPixelFormat::from_name(&AV_PIX_FMT_NV12.name()),
+            AV_PIX_FMT_NV12
+        );
+        assert_eq!(AVPixelFormat::from_name("not-a-format"), AV_PIX_FMT_NONE);
+        assert_eq!(AV_PIX_FMT_NONE.name(), "none");
+        assert_eq!(AV_PIX_FMT_YUV420P.bits_per_pixel(), 12);
+        assert_eq!(AV_PIX_FMT_RGB24.bits_per_pixel(), 24);
+        assert_eq!(AV_PIX_FMT_NONE.bits_per_pixel(), 0);
+        let desc = AV_PIX_FMT_YUV420P.descriptor().unwrap();
+        assert_eq!(desc.nb_components, 3);
+        assert!(AV_PIX_FMT_NONE.descriptor().is_none());
+    }
+}
@@ -1,5 +1,9 @@
-use crate::AVRational;
+use crate::{av_add_q, av_d2q, av_div_q, av_mul_q, av_sub_q, AVRational};
 use libc::{c_double, c_int};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::ops::{Add, Div, Mul, Sub};
+use std::time::Duration;
 
 impl Default for AVRational {
     fn default() -> Self {
@@ -17,6 +21,129 @@ impl AVRational {
     pub fn with_normalize(value: i32) -> Self {
         AVRational { num: 1, den: value }
     }
+
+    /// Convert the `value` to the nearest rational whose numerator and
+    /// denominator are not greater than `max`.
+    pub fn from_f64(value: f64, max: i32) -> Self {
+        unsafe { av_d2q(value, max) }
+    }
+
+    /// Convert the timestamp `ts` in this time base to a `Duration`, the
+    /// negative results and the zero denominator are `Duration::ZERO`, the
+    /// results too large to represent are `Duration::MAX`.
+    pub fn to_duration(self, ts: i64) -> Duration {
+        if self.den == 0 {
+            return Duration::ZERO;
+        }
+        let nanos = i128::from(ts) * i128::from(self.num) * 1_000_000_000 / i128::from(self.den);
+        if nanos <= 0 {
+            return Duration::ZERO;
+        }
+        match u64::try_from(nanos / 1_000_000_000) {
+            Ok(secs) => Duration::new(secs, (nanos % 1_000_000_000) as u32),
+            Err(_) => Duration::MAX,
+        }
+    }
+
+    /// Snap the measured frame rate to the closest standard frame rate, such
+    /// as `23.98` to `24000/1001`.
+    ///
+    /// The rate is only snapped if it is within 0.05 fps of a standard rate,
+    /// otherwise it is returned unchanged.
+    pub fn nearest_common_fps(self) -> AVRational {
+        const COMMON_FPS: [AVRational; 8] = [
+            AVRational {
+                num: 24000,
+                den: 1001,
+            },
+            AVRational { num: 24, den: 1 },
+            AVRational { num: 25, den: 1 },
+            AVRational {
+                num: 30000,
+                den: 1001,
+            },
+            AVRational { num: 30, den: 1 },
+            AVRational { num: 50, den: 1 },
+            AVRational {
+                num: 60000,
+                den: 1001,
+            },
+            AVRational { num: 60, den: 1 },
+        ];
+        const TOLERANCE: f64 = 0.05;
+
+        if self.num <= 0 || self.den <= 0 {
+            return self;
+        }
+        let value = unsafe { av_q2d(self) };
+        let mut nearest = self;
+        let mut min_dist = TOLERANCE;
+        for &fps in COMMON_FPS.iter() {
+            if unsafe { av_cmp_q(self, fps) } == 0 {
+                return fps;
+            }
+            let dist = (unsafe { av_q2d(fps) } - value).abs();
+            if dist <= min_dist {
+                nearest = fps;
+                min_dist = dist;
+            }
+        }
+        nearest
+    }
+}
+
+impl From<AVRational> for f64 {
+    fn from(q: AVRational) -> f64 {
+        unsafe { av_q2d(q) }
+    }
+}
+
+impl Add for AVRational {
+    type Output = AVRational;
+
+    fn add(self, rhs: AVRational) -> AVRational {
+        unsafe { av_add_q(self, rhs) }
+    }
+}
+
+impl Sub for AVRational {
+    type Output = AVRational;
+
+    fn sub(self, rhs: AVRational) -> AVRational {
+        unsafe { av_sub_q(self, rhs) }
+    }
+}
+
+impl Mul for AVRational {
+    type Output = AVRational;
+
+    fn mul(self, rhs: AVRational) -> AVRational {
+        unsafe { av_mul_q(self, rhs) }
+    }
+}
+
+impl Div for AVRational {
+    type Output = AVRational;
+
+    fn div(self, rhs: AVRational) -> AVRational {
+        unsafe { av_div_q(self, rhs) }
+    }
+}
+
+/// Compares the values of the rationals, `None` if undefined, such as `0/0`.
+///
+/// To stay consistent with the derived `PartialEq` which compares the fields,
+/// the rationals of the same value but different fields, such as `1/2` and
+/// `2/4`, are unordered and compared as `None`.
+impl PartialOrd for AVRational {
+    fn partial_cmp(&self, other: &AVRational) -> Option<Ordering> {
+        match unsafe { av_cmp_q(*self, *other) } {
+            0 if self == other => Some(Ordering::Equal),
+            1 => Some(Ordering::Greater),
+            -1 => Some(Ordering::Less),
+            _ => None,
+        }
+    }
 }
 
 /// # Safety
@@ -55,3 +182,70 @@ pub unsafe fn av_inv_q(q: AVRational) -> AVRational {
         den: q.num,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_common_fps() {
+        let ntsc_film = AVRational::new(24000, 1001);
+        assert_eq!(AVRational::new(2398, 100).nearest_common_fps(), ntsc_film);
+        assert_eq!(AVRational::new(23976, 1000).nearest_common_fps(), ntsc_film);
+        assert_eq!(AVRational::new(48000, 2002).nearest_common_fps(), ntsc_film);
+        assert_eq!(
+            AVRational::new(2501, 100).nearest_common_fps(),
+            AVRational::new(25, 1)
+        );
+        assert_eq!(
+            AVRational::new(2997, 100).nearest_common_fps(),
+            AVRational::new(30000, 1001)
+        );
+        assert_eq!(
+            AVRational::new(15, 1).nearest_common_fps(),
+            AVRational::new(15, 1)
+        );
+        assert_eq!(
+            AVRational::new(0, 1).nearest_common_fps(),
+            AVRational::new(0, 1)
+        );
+    }
+
+    #[test]
+    fn test_avrational_ops() {
+        let a = AVRational::new(1, 2);
+        let b = AVRational::new(1, 3);
+        assert_eq!(a + b, AVRational::new(5, 6));
+        assert_eq!(a - b, AVRational::new(1, 6));
+        assert_eq!(a * b, AVRational::new(1, 6));
+        assert_eq!(a / b, AVRational::new(3, 2));
+
+        assert!(a > b);
+        assert!(b < a);
+        assert_eq!(a.partial_cmp(&AVRational::new(1, 2)), Some(Ordering::Equal));
+        assert_eq!(a.partial_cmp(&AVRational::new(2, 4)), None);
+        assert!(a <= AVRational::new(1, 2));
+        assert_eq!(AVRational::new(0, 0).partial_cmp(&a), None);
+    }
+
+    #[test]
+    fn test_avrational_f64_duration() {
+        let q = AVRational::from_f64(0.5, 100);
+        assert_eq!(q, AVRational::new(1, 2));
+        assert_eq!(f64::from(q), 0.5);
+        assert_eq!(
+            AVRational::from_f64(29.97, 100000),
+            AVRational::new(2997, 100)
+        );
+
+        let tb = AVRational::new(1, 90000);
+        assert_eq!(tb.to_duration(180000), Duration::from_secs(2));
+        assert_eq!(tb.to_duration(135000), Duration::from_millis(1500));
+        assert_eq!(tb.to_duration(-90000), Duration::ZERO);
+        assert_eq!(AVRational::new(1, 0).to_duration(1000), Duration::ZERO);
+        assert_eq!(
+            AVRational::new(i32::MAX, 1).to_duration(i64::MAX),
+            Duration::MAX
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{
+    av_sample_fmt_is_planar, av_samples_copy, av_samples_get_buffer_size, AVSampleFormat, AvError,
+    AVERROR,
+};
+use libc::EINVAL;
+use std::borrow::Cow;
+use std::ffi::{CStr, CString};
+
+impl AVSampleFormat {
+    /// Returns the sample format of the raw value, such as `AVFrame.format`,
+    /// `None` if out of range.
+    pub fn from_raw(v: i32) -> Option<AVSampleFormat> {
+        use AVSampleFormat::*;
+        if v >= AV_SAMPLE_FMT_NONE as i32 && v < AV_SAMPLE_FMT_NB as i32 {
+            Some(unsafe { std::mem::transmute::<i32, AVSampleFormat>(v) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of bytes per sample, 0 if unknown.
+    pub fn bytes_per_sample(self) -> i32 {
+        unsafe { crate::av_get_bytes_per_sample(self) }
+    }
+
+    /// Returns the name of the sample format, `"none"` if unknown.
+    pub fn name(self) -> Cow<'static, str> {
+        unsafe {
+            let name = crate::av_get_sample_fmt_name(self);
+            if name.is_null() {
+                Cow::Borrowed("none")
+            } else {
+                CStr::from_ptr(name).to_string_lossy()
+            }
+        }
+    }
+
+    /// Returns the sample format of the `name`, `AV_SAMPLE_FMT_NONE` if not found.
+    pub fn from_name(name: &str) -> AVSampleFormat {
+        match CString::new(name) {
+            Ok(name) => unsafe { crate::av_get_sample_fmt(name.as_ptr()) },
+            Err(_) => AVSampleFormat::AV_SAMPLE_FMT_NONE,
+        }
+    }
+
+    /// Returns `true` if the sample format is planar.
+    pub fn is_planar(self) -> bool {
+        unsafe { av_sample_fmt_is_planar(self) != 0 }
+    }
+
+    /// Returns the packed alternative form of the sample format.
+    pub fn packed(self) -> AVSampleFormat {
+        unsafe { crate::av_get_packed_sample_fmt(self) }
+    }
+
+    /// Returns the planar alternative form of the sample format.
+    pub fn planar(self) -> AVSampleFormat {
+        unsafe { crate::av_get_planar_sample_fmt(self) }
+    }
+}
+
+/// Returns the size in bytes of the buffer for the audio samples.
+///
+/// The `align` is the buffer size alignment, 0 for the default and 1 for no alignment.
+pub fn samples_buffer_size(
+    channels: i32,
+    nb_samples: i32,
+    fmt: AVSampleFormat,
+    align: i32,
+) -> Result<i32, AvError> {
+    let ret = unsafe {
+        av_samples_get_buffer_size(std::ptr::null_mut(), channels, nb_samples, fmt, align)
+    };
+    if ret < 0 {
+        Err(AvError(ret))
+    } else {
+        Ok(ret)
+    }
+}
+
+/// Copy `nb_samples` samples from `src` at `src_offset` to `dst` at `dst_offset`.
+///
+/// The `dst` and `src` contain one pointer per channel for the planar formats,
+/// or a single pointer for the packed formats, `EINVAL` is returned if not.
+///
+/// # Safety
+///
+/// Each plane of `src` must be valid for reads of `src_offset + nb_samples`
+/// samples and each plane of `dst` must be valid for writes of
+/// `dst_offset + nb_samples` samples, for the `channels` of the format, and
+/// the planes must not overlap.
+pub unsafe fn samples_copy(
+    dst: &mut [*mut u8],
+    src: &[*const u8],
+    dst_offset: i32,
+    src_offset: i32,
+    nb_samples: i32,
+    channels: i32,
+    fmt: AVSampleFormat,
+) -> Result<(), AvError> {
+    let planes = if fmt.is_planar() {
+        channels.max(0) as usize
+    } else {
+        1
+    };
+    if dst.len() < planes || src.len() < planes {
+        return Err(AvError(AVERROR(EINVAL)));
+    }
+    let ret = av_samples_copy(
+        dst.as_mut_ptr(),
+        src.as_ptr() as *const *mut u8,
+        dst_offset,
+        src_offset,
+        nb_samples,
+        channels,
+        fmt,
+    );
+    if ret < 0 {
+        Err(AvError(ret))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AVSampleFormat::*;
+
+    #[test]
+    fn test_samples_buffer_size() {
+        assert_eq!(samples_buffer_size(2, 1024, AV_SAMPLE_FMT_S16, 1), Ok(4096));
+        assert_eq!(
+            samples_buffer_size(2, 1024, AV_SAMPLE_FMT_FLTP, 1),
+            Ok(8192)
+        );
+        assert_eq!(samples_buffer_size(1, 1000, AV_SAMPLE_FMT_U8, 0), Ok(1024));
+        assert!(samples_buffer_size(0, 1024, AV_SAMPLE_FMT_S16, 1).is_err());
+        assert!(samples_buffer_size(2, 1024, AV_SAMPLE_FMT_NONE, 1).is_err());
+    }
+
+    #[test]
+    fn test_sample_fmt_from_raw() {
+        assert_eq!(AVSampleFormat::from_raw(-1), Some(AV_SAMPLE_FMT_NONE));
+        assert_eq!(AVSampleFormat::from_raw(1), Some(AV_SAMPLE_FMT_S16));
+        assert_eq!(AVSampleFormat::from_raw(AV_SAMPLE_FMT_NB as i32), None);
+        assert_eq!(AVSampleFormat::from_raw(-2), None);
+    }
+
+    #[test]
+    fn test_sample_fmt_helpers() {
+        assert_eq!(AV_SAMPLE_FMT_S16.bytes_per_sample(), 2);
+        assert_eq!(AV_SAMPLE_FMT_DBLP.bytes_per_sample(), 8);
+        assert_eq!(AV_SAMPLE_FMT_NONE.bytes_per_sample(), 0);
+        assert!(!AV_SAMPLE_FMT_S16.is_planar());
+        assert!(AV_SAMPLE_FMT_FLTP.is_planar());
+        assert_eq!(AV_SAMPLE_FMT_FLTP.name(), "fltp");
+        assert_eq!(AV_SAMPLE_FMT_NONE.name(), "none");
+        assert_eq!(AVSampleFormat::from_name("s16"), AV_SAMPLE_FMT_S16);
+        assert_eq!(AVSampleFormat::from_name("bogus"), AV_SAMPLE_FMT_NONE);
+        assert_eq!(AV_SAMPLE_FMT_FLTP.packed(), AV_SAMPLE_FMT_FLT);
+        assert_eq!(AV_SAMPLE_FMT_S16.planar(), AV_SAMPLE_FMT_S16P);
+        assert_eq!(AV_SAMPLE_FMT_S16.packed(), AV_SAMPLE_FMT_S16);
+    }
+
+    #[test]
+    fn test_samples_copy() {
+        let left: Vec<f32> = (0..64).map(|v| v as f32).collect();
+        let right: Vec<f32> = (0..64).map(|v| -(v as f32)).collect();
+        let mut dst_left = vec![0f32; 32];
+        let mut dst_right = vec![0f32; 32];
+        let src = [left.as_ptr() as *const u8, right.as_ptr() as *const u8];
+        let mut dst = [
+            dst_left.as_mut_ptr() as *mut u8,
+            dst_right.as_mut_ptr() as *mut u8,
+        ];
+        unsafe { samples_copy(&mut dst, &src, 8, 16, 24, 2, AV_SAMPLE_FMT_FLTP) }.unwrap();
+        assert_eq!(dst_left[..8], [0f32; 8]);
+        assert_eq!(dst_left[8..], left[16..40]);
+        assert_eq!(dst_right[8..], right[16..40]);
+
+        assert_eq!(
+            unsafe { samples_copy(&mut dst[..1], &src, 0, 0, 8, 2, AV_SAMPLE_FMT_FLTP) },
+            Err(AvError(AVERROR(EINVAL)))
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{
+    av_free, av_twofish_alloc, av_twofish_crypt, av_twofish_init, AvError, AVERROR, AVTWOFISH,
+};
+use libc::{c_void, EINVAL, ENOMEM};
+
+/// The Twofish block cipher.
+#[derive(Debug)]
+pub struct Twofish {
+    ptr: *mut AVTWOFISH,
+}
+
+impl Twofish {
+    /// Create a new cipher with the 128, 192 or 256-bit key.
+    pub fn new(key: &[u8]) -> Result<Self, AvError> {
+        if !matches!(key.len(), 16 | 24 | 32) {
+            return Err(AvError(AVERROR(EINVAL)));
+        }
+        let ptr = unsafe { av_twofish_alloc() };
+        if ptr.is_null() {
+            return Err(AvError(AVERROR(ENOMEM)));
+        }
+        let twofish = Self { ptr };
+        let ret = unsafe { av_twofish_init(ptr, key.as_ptr(), key.len() as i32 * 8) };
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(twofish)
+        }
+    }
+
+    /// Encrypt or decrypt the `src` into `dst` by 16 bytes blocks.
+    ///
+    /// The ECB mode is used if `iv` is `None`, otherwise the CBC mode is used
+    /// and the `iv` is updated for the next call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `src` is not a multiple of 16 or `dst` is shorter than `src`.
+    pub fn crypt(&mut self, dst: &mut [u8], src: &[u8], iv: Option<&mut [u8; 16]>, decrypt: bool) {
+        assert_eq!(src.len() % 16, 0, "invalid Twofish data length");
+        assert!(dst.len() >= src.len(), "Twofish output buffer too small");
+        let iv = iv.map_or(std::ptr::null_mut(), |v| v.as_mut_ptr());
+        unsafe {
+            av_twofish_crypt(
+                self.ptr,
+                dst.as_mut_ptr(),
+                src.as_ptr(),
+                (src.len() / 16) as i32,
+                iv,
+                decrypt as i32,
+            )
+        }
+    }
+}
+
+impl Drop for Twofish {
+    fn drop(&mut self) {
+        unsafe { av_free(self.ptr as *mut c_void) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_twofish() {
+        assert_eq!(Twofish::new(&[0; 8]).unwrap_err(), AvError(AVERROR(EINVAL)));
+
+        let mut twofish = Twofish::new(&[0; 16]).unwrap();
+        let mut ct = [0u8; 16];
+        twofish.crypt(&mut ct, &[0; 16], None, false);
+        assert_eq!(
+            ct,
+            [
+                0x9f, 0x58, 0x9f, 0x5c, 0xf6, 0x12, 0x2c, 0x32, 0xb6, 0xbf, 0xec, 0x2f, 0x2a, 0xe8,
+                0xc3, 0x5a
+            ]
+        );
+
+        for &bits in [128, 192, 256].iter() {
+            let key: Vec<u8> = (0..bits / 8).map(|v| v as u8).collect();
+            let mut twofish = Twofish::new(&key).unwrap();
+            let src = b"The quick brown fox jumps over a";
+            let mut iv = [0x11u8; 16];
+            let mut ct = [0u8; 32];
+            twofish.crypt(&mut ct, src, Some(&mut iv), false);
+            assert_ne!(&ct, src);
+            let mut iv = [0x11u8; 16];
+            let mut pt = [0u8; 32];
+            twofish.crypt(&mut pt, &ct, Some(&mut iv), true);
+            assert_eq!(&pt, src);
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{AVMediaType, AVRational, AV_TIME_BASE, MKTAG};
 use libc::c_int;
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::fmt;
 
 pub const AV_NOPTS_VALUE: i64 = 0x8000000000000000u64 as i64;
 pub const AV_TIME_BASE_Q: AVRational = AVRational {
@@ -10,9 +13,103 @@ pub const AV_TIME_BASE_Q: AVRational = AVRational {
 pub const AV_CODEC_TAG_AVC1: u32 = MKTAG!(b'a', b'v', b'c', b'1') as u32;
 pub const AV_CODEC_TAG_HEV1: u32 = MKTAG!(b'h', b'e', b'v', b'1') as u32;
 pub const AV_CODEC_TAG_HVC1: u32 = MKTAG!(b'h', b'v', b'c', b'1') as u32;
+pub const AV_CODEC_TAG_H264: u32 = MKTAG!(b'H', b'2', b'6', b'4') as u32;
+pub const AV_CODEC_TAG_MP4V: u32 = MKTAG!(b'm', b'p', b'4', b'v') as u32;
+pub const AV_CODEC_TAG_XVID: u32 = MKTAG!(b'X', b'V', b'I', b'D') as u32;
+pub const AV_CODEC_TAG_MJPG: u32 = MKTAG!(b'M', b'J', b'P', b'G') as u32;
+pub const AV_CODEC_TAG_AV01: u32 = MKTAG!(b'a', b'v', b'0', b'1') as u32;
+pub const AV_CODEC_TAG_VP09: u32 = MKTAG!(b'v', b'p', b'0', b'9') as u32;
+pub const AV_CODEC_TAG_SOWT: u32 = MKTAG!(b's', b'o', b'w', b't') as u32;
+pub const AV_CODEC_TAG_TWOS: u32 = MKTAG!(b't', b'w', b'o', b's') as u32;
+pub const AV_CODEC_TAG_LPCM: u32 = MKTAG!(b'l', b'p', b'c', b'm') as u32;
+pub const AV_CODEC_TAG_IN24: u32 = MKTAG!(b'i', b'n', b'2', b'4') as u32;
+pub const AV_CODEC_TAG_IN32: u32 = MKTAG!(b'i', b'n', b'3', b'2') as u32;
+pub const AV_CODEC_TAG_FL32: u32 = MKTAG!(b'f', b'l', b'3', b'2') as u32;
+pub const AV_CODEC_TAG_FL64: u32 = MKTAG!(b'f', b'l', b'6', b'4') as u32;
+
+/// Returns the string of the fourcc `tag`, such as `"avc1"`, the unprintable
+/// characters are written as `[n]` like `av_fourcc_make_string()`.
+pub fn fourcc_to_string(tag: u32) -> String {
+    let mut s = String::with_capacity(4);
+    for &c in tag.to_le_bytes().iter() {
+        if c.is_ascii_alphanumeric() || matches!(c, b'.' | b' ' | b'-' | b'_') {
+            s.push(c as char);
+        } else {
+            s.push_str(&format!("[{}]", c));
+        }
+    }
+    s
+}
 
 impl Default for AVMediaType {
     fn default() -> Self {
         AVMediaType::AVMEDIA_TYPE_UNKNOWN
     }
 }
+
+impl AVMediaType {
+    /// Returns the name of the media type, such as `"video"`, or `"unknown"`.
+    pub fn name(self) -> Cow<'static, str> {
+        unsafe {
+            let name = crate::av_get_media_type_string(self);
+            if name.is_null() {
+                Cow::Borrowed("unknown")
+            } else {
+                CStr::from_ptr(name).to_string_lossy()
+            }
+        }
+    }
+
+    /// Returns the media type of the `name`, `AVMEDIA_TYPE_UNKNOWN` if not found.
+    pub fn from_name(name: &str) -> AVMediaType {
+        use AVMediaType::*;
+        [
+            AVMEDIA_TYPE_VIDEO,
+            AVMEDIA_TYPE_AUDIO,
+            AVMEDIA_TYPE_DATA,
+            AVMEDIA_TYPE_SUBTITLE,
+            AVMEDIA_TYPE_ATTACHMENT,
+        ]
+        .iter()
+        .copied()
+        .find(|t| t.name() == name)
+        .unwrap_or(AVMEDIA_TYPE_UNKNOWN)
+    }
+}
+
+impl fmt::Display for AVMediaType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AVMediaType::*;
+
+    #[test]
+    fn test_fourcc_to_string() {
+        assert_eq!(fourcc_to_string(AV_CODEC_TAG_AVC1), "avc1");
+        assert_eq!(fourcc_to_string(AV_CODEC_TAG_H264), "H264");
+        assert_eq!(fourcc_to_string(AV_CODEC_TAG_VP09), "vp09");
+        assert_eq!(fourcc_to_string(AV_CODEC_TAG_SOWT), "sowt");
+        assert_eq!(
+            fourcc_to_string(MKTAG!(b'r', b'a', b'w', b' ') as u32),
+            "raw "
+        );
+        assert_eq!(fourcc_to_string(1), "[1][0][0][0]");
+    }
+
+    #[test]
+    fn test_avmediatype_name() {
+        assert_eq!(AVMEDIA_TYPE_VIDEO.name(), "video");
+        assert_eq!(AVMEDIA_TYPE_AUDIO.to_string(), "audio");
+        assert_eq!(AVMEDIA_TYPE_UNKNOWN.name(), "unknown");
+        assert_eq!(AVMEDIA_TYPE_NB.name(), "unknown");
+        assert_eq!(AVMediaType::from_name("video"), AVMEDIA_TYPE_VIDEO);
+        assert_eq!(AVMediaType::from_name("subtitle"), AVMEDIA_TYPE_SUBTITLE);
+        assert_eq!(AVMediaType::from_name("unknown"), AVMEDIA_TYPE_UNKNOWN);
+        assert_eq!(AVMediaType::from_name("bogus"), AVMEDIA_TYPE_UNKNOWN);
+    }
+}
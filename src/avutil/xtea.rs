@@ -0,0 +1,79 @@
+use crate::{av_free, av_xtea_alloc, av_xtea_crypt, av_xtea_init, AvError, AVERROR, AVXTEA};
+use libc::{c_void, ENOMEM};
+
+/// The XTEA block cipher, the key and blocks are big-endian.
+#[derive(Debug)]
+pub struct Xtea {
+    ptr: *mut AVXTEA,
+}
+
+impl Xtea {
+    /// Create a new cipher with the 128-bit key.
+    pub fn new(key: &[u8; 16]) -> Result<Self, AvError> {
+        let ptr = unsafe { av_xtea_alloc() };
+        if ptr.is_null() {
+            return Err(AvError(AVERROR(ENOMEM)));
+        }
+        unsafe { av_xtea_init(ptr, key.as_ptr()) };
+        Ok(Self { ptr })
+    }
+
+    /// Encrypt or decrypt the `src` into `dst` by 8 bytes blocks.
+    ///
+    /// The ECB mode is used if `iv` is `None`, otherwise the CBC mode is used
+    /// and the `iv` is updated for the next call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `src` is not a multiple of 8 or `dst` is shorter than `src`.
+    pub fn crypt(&mut self, dst: &mut [u8], src: &[u8], iv: Option<&mut [u8; 8]>, decrypt: bool) {
+        assert_eq!(src.len() % 8, 0, "invalid XTEA data length");
+        assert!(dst.len() >= src.len(), "XTEA output buffer too small");
+        let iv = iv.map_or(std::ptr::null_mut(), |v| v.as_mut_ptr());
+        unsafe {
+            av_xtea_crypt(
+                self.ptr,
+                dst.as_mut_ptr(),
+                src.as_ptr(),
+                (src.len() / 8) as i32,
+                iv,
+                decrypt as i32,
+            )
+        }
+    }
+}
+
+impl Drop for Xtea {
+    fn drop(&mut self) {
+        unsafe { av_free(self.ptr as *mut c_void) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xtea() {
+        let key: [u8; 16] = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+            0x0e, 0x0f,
+        ];
+        let mut xtea = Xtea::new(&key).unwrap();
+        let mut ct = [0u8; 8];
+        xtea.crypt(&mut ct, b"ABCDEFGH", None, false);
+        assert_eq!(ct, [0x49, 0x7d, 0xf3, 0xd0, 0x72, 0x61, 0x2c, 0xb5]);
+        let mut pt = [0u8; 8];
+        xtea.crypt(&mut pt, &ct, None, true);
+        assert_eq!(&pt, b"ABCDEFGH");
+
+        let src = b"0123456789abcdef";
+        let mut iv = [0x5au8; 8];
+        let mut ct = [0u8; 16];
+        xtea.crypt(&mut ct, src, Some(&mut iv), false);
+        let mut iv = [0x5au8; 8];
+        let mut pt = [0u8; 16];
+        xtea.crypt(&mut pt, &ct, Some(&mut iv), true);
+        assert_eq!(&pt, src);
+    }
+}
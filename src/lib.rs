@@ -14,8 +14,28 @@ include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 mod avcodec;
 pub use avcodec::*;
 
+#[cfg(feature = "avdevice")]
+mod avdevice;
+#[cfg(feature = "avdevice")]
+pub use avdevice::*;
+
+#[cfg(feature = "avfilter")]
+mod avfilter;
+#[cfg(feature = "avfilter")]
+pub use avfilter::*;
+
 mod avformat;
 pub use avformat::*;
 
 mod avutil;
 pub use avutil::*;
+
+#[cfg(feature = "swresample")]
+mod swresample;
+#[cfg(feature = "swresample")]
+pub use swresample::*;
+
+#[cfg(feature = "swscale")]
+mod swscale;
+#[cfg(feature = "swscale")]
+pub use swscale::*;
@@ -0,0 +1,172 @@
+use crate::{
+    swr_alloc_set_opts, swr_convert, swr_free, swr_get_delay, swr_init, AVSampleFormat, AvError,
+    SwrContext, AVERROR,
+};
+use libc::ENOMEM;
+
+/// The audio resampler and sample format converter.
+#[derive(Debug)]
+pub struct Resampler {
+    ptr: *mut SwrContext,
+}
+
+impl Resampler {
+    /// Create a new resampler for the channel layouts, sample formats and sample rates.
+    pub fn new(
+        out_ch_layout: u64,
+        out_fmt: AVSampleFormat,
+        out_rate: i32,
+        in_ch_layout: u64,
+        in_fmt: AVSampleFormat,
+        in_rate: i32,
+    ) -> Result<Self, AvError> {
+        let ptr = unsafe {
+            swr_alloc_set_opts(
+                std::ptr::null_mut(),
+                out_ch_layout as i64,
+                out_fmt,
+                out_rate,
+                in_ch_layout as i64,
+                in_fmt,
+                in_rate,
+                0,
+                std::ptr::null_mut(),
+            )
+        };
+        if ptr.is_null() {
+            return Err(AvError(AVERROR(ENOMEM)));
+        }
+        let resampler = Self { ptr };
+        let ret = unsafe { swr_init(ptr) };
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(resampler)
+        }
+    }
+
+    /// Returns the raw pointer of the resampler context.
+    pub fn as_ptr(&self) -> *const SwrContext {
+        self.ptr
+    }
+
+    /// Returns the mutable raw pointer of the resampler context.
+    pub fn as_mut_ptr(&mut self) -> *mut SwrContext {
+        self.ptr
+    }
+
+    /// Convert `in_count` samples per channel from `input` into `out`, returns
+    /// the number of samples per channel written.
+    ///
+    /// The `out` and `input` contain one pointer per plane, a single pointer
+    /// for the packed formats.
+    ///
+    /// # Safety
+    ///
+    /// The `out` and `input` must contain a pointer for each plane of the
+    /// output and input formats, each `out` plane must be valid for writes of
+    /// `out_count` samples and each `input` plane must be valid for reads of
+    /// `in_count` samples.
+    pub unsafe fn convert(
+        &mut self,
+        out: &mut [*mut u8],
+        out_count: i32,
+        input: &[*const u8],
+        in_count: i32,
+    ) -> Result<i32, AvError> {
+        let ret = swr_convert(
+            self.ptr,
+            out.as_mut_ptr(),
+            out_count,
+            input.as_ptr() as *mut *const u8,
+            in_count,
+        );
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(ret)
+        }
+    }
+
+    /// Returns the delay of the buffered samples in `1/base` units, such as the
+    /// output sample rate for the number of pending output samples.
+    pub fn delay(&self, base: i64) -> i64 {
+        unsafe { swr_get_delay(self.ptr, base) }
+    }
+
+    /// Drain the buffered samples into `out` at the end of stream, returns the
+    /// number of samples per channel written, 0 if fully drained.
+    ///
+    /// # Safety
+    ///
+    /// The `out` must contain a pointer for each plane of the output format,
+    /// each valid for writes of `out_count` samples.
+    pub unsafe fn flush(&mut self, out: &mut [*mut u8], out_count: i32) -> Result<i32, AvError> {
+        let ret = swr_convert(
+            self.ptr,
+            out.as_mut_ptr(),
+            out_count,
+            std::ptr::null_mut(),
+            0,
+        );
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(ret)
+        }
+    }
+}
+
+impl Drop for Resampler {
+    fn drop(&mut self) {
+        unsafe { swr_free(&mut self.ptr) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AVSampleFormat::*, AV_CH_LAYOUT_MONO};
+
+    #[test]
+    fn test_resampler_flush() {
+        let mut swr = Resampler::new(
+            AV_CH_LAYOUT_MONO,
+            AV_SAMPLE_FMT_S16,
+            48000,
+            AV_CH_LAYOUT_MONO,
+            AV_SAMPLE_FMT_S16,
+            44100,
+        )
+        .unwrap();
+        let input: Vec<i16> = (0..1024).map(|v| ((v % 64) * 256) as i16).collect();
+        let mut output = vec![0i16; 2048];
+
+        let converted = unsafe {
+            swr.convert(
+                &mut [output.as_mut_ptr() as *mut u8],
+                output.len() as i32,
+                &[input.as_ptr() as *const u8],
+                input.len() as i32,
+            )
+        }
+        .unwrap();
+        assert!(converted < 1114);
+        assert!(swr.delay(48000) > 0);
+
+        let residual = &mut output[converted as usize..];
+        let flushed = unsafe {
+            swr.flush(
+                &mut [residual.as_mut_ptr() as *mut u8],
+                residual.len() as i32,
+            )
+        }
+        .unwrap();
+        assert!(flushed > 0);
+        assert!((1110..=1120).contains(&(converted + flushed)));
+        assert_eq!(
+            unsafe { swr.flush(&mut [output.as_mut_ptr() as *mut u8], output.len() as i32) },
+            Ok(0)
+        );
+    }
+}
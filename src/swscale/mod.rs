@@ -0,0 +1,226 @@
+use crate::{
+    sws_freeContext, sws_getCachedContext, sws_getContext, sws_scale, AVPixelFormat, AvError,
+    SwsContext, AVERROR,
+};
+use libc::EINVAL;
+
+/// The image scaler and pixel format converter.
+#[derive(Debug)]
+pub struct Scaler {
+    ptr: *mut SwsContext,
+}
+
+impl Scaler {
+    /// Create a new scaler with the `flags`, such as `SWS_BILINEAR`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        src_w: i32,
+        src_h: i32,
+        src_fmt: AVPixelFormat,
+        dst_w: i32,
+        dst_h: i32,
+        dst_fmt: AVPixelFormat,
+        flags: i32,
+    ) -> Result<Self, AvError> {
+        Self::get_cached(None, src_w, src_h, src_fmt, dst_w, dst_h, dst_fmt, flags)
+    }
+
+    /// Returns the `prev` scaler if the parameters are unchanged, otherwise
+    /// the `prev` is freed and a new scaler is created.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_cached(
+        prev: Option<Scaler>,
+        src_w: i32,
+        src_h: i32,
+        src_fmt: AVPixelFormat,
+        dst_w: i32,
+        dst_h: i32,
+        dst_fmt: AVPixelFormat,
+        flags: i32,
+    ) -> Result<Self, AvError> {
+        let ptr = unsafe {
+            match prev {
+                // The previous context is always taken over, it's either
+                // returned as is or freed.
+                Some(prev) => sws_getCachedContext(
+                    prev.into_raw(),
+                    src_w,
+                    src_h,
+                    src_fmt,
+                    dst_w,
+                    dst_h,
+                    dst_fmt,
+                    flags,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                    std::ptr::null(),
+                ),
+                None => sws_getContext(
+                    src_w,
+                    src_h,
+                    src_fmt,
+                    dst_w,
+                    dst_h,
+                    dst_fmt,
+                    flags,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                    std::ptr::null(),
+                ),
+            }
+        };
+        if ptr.is_null() {
+            Err(AvError(AVERROR(EINVAL)))
+        } else {
+            Ok(Self { ptr })
+        }
+    }
+
+    /// Returns the raw pointer of the scaler context.
+    pub fn as_ptr(&self) -> *const SwsContext {
+        self.ptr
+    }
+
+    /// Returns the mutable raw pointer of the scaler context.
+    pub fn as_mut_ptr(&mut self) -> *mut SwsContext {
+        self.ptr
+    }
+
+    fn into_raw(self) -> *mut SwsContext {
+        let ptr = self.ptr;
+        std::mem::forget(self);
+        ptr
+    }
+
+    /// Scale the slice of `src_slice_h` lines starting at `src_slice_y` in the
+    /// source image, returns the height of the output slice.
+    ///
+    /// All of the data and line size slices must contain 4 entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any slice contains less than 4 entries.
+    ///
+    /// # Safety
+    ///
+    /// Each used plane of `src_data` must be valid for reads of its line size
+    /// times the plane height of the source slice, and each used plane of
+    /// `dst_data` must be valid for writes of its line size times the plane
+    /// height of the destination image, in the formats and sizes of the
+    /// scaler.
+    pub unsafe fn scale(
+        &mut self,
+        src_data: &[*const u8],
+        src_linesize: &[i32],
+        src_slice_y: i32,
+        src_slice_h: i32,
+        dst_data: &[*mut u8],
+        dst_linesize: &[i32],
+    ) -> Result<i32, AvError> {
+        assert!(
+            src_data.len() >= 4 && src_linesize.len() >= 4,
+            "invalid source planes"
+        );
+        assert!(
+            dst_data.len() >= 4 && dst_linesize.len() >= 4,
+            "invalid destination planes"
+        );
+        let ret = sws_scale(
+            self.ptr,
+            src_data.as_ptr(),
+            src_linesize.as_ptr(),
+            src_slice_y,
+            src_slice_h,
+            dst_data.as_ptr(),
+            dst_linesize.as_ptr(),
+        );
+        if ret < 0 {
+            Err(AvError(ret))
+        } else {
+            Ok(ret)
+        }
+    }
+}
+
+impl Drop for Scaler {
+    fn drop(&mut self) {
+        unsafe { sws_freeContext(self.ptr) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AVPixelFormat::*, SWS_BILINEAR};
+
+    #[test]
+    fn test_scaler_get_cached() {
+        let scaler = Scaler::new(
+            16,
+            16,
+            AV_PIX_FMT_GRAY8,
+            8,
+            8,
+            AV_PIX_FMT_GRAY8,
+            SWS_BILINEAR,
+        )
+        .unwrap();
+        let ptr = scaler.as_ptr();
+        let mut scaler = Scaler::get_cached(
+            Some(scaler),
+            16,
+            16,
+            AV_PIX_FMT_GRAY8,
+            8,
+            8,
+            AV_PIX_FMT_GRAY8,
+            SWS_BILINEAR,
+        )
+        .unwrap();
+        assert_eq!(scaler.as_ptr(), ptr);
+
+        let src = vec![128u8; 16 * 16];
+        let mut dst = vec![0u8; 8 * 8];
+        let src_data = [
+            src.as_ptr(),
+            std::ptr::null(),
+            std::ptr::null(),
+            std::ptr::null(),
+        ];
+        let dst_data = [
+            dst.as_mut_ptr(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        ];
+        let h = unsafe { scaler.scale(&src_data, &[16, 0, 0, 0], 0, 16, &dst_data, &[8, 0, 0, 0]) }
+            .unwrap();
+        assert_eq!(h, 8);
+        assert!(dst.iter().all(|&v| (127..=129).contains(&v)));
+
+        let scaler = Scaler::get_cached(
+            Some(scaler),
+            16,
+            16,
+            AV_PIX_FMT_GRAY8,
+            4,
+            4,
+            AV_PIX_FMT_GRAY8,
+            SWS_BILINEAR,
+        )
+        .unwrap();
+        assert!(!scaler.as_ptr().is_null());
+
+        assert!(Scaler::get_cached(
+            Some(scaler),
+            0,
+            0,
+            AV_PIX_FMT_GRAY8,
+            4,
+            4,
+            AV_PIX_FMT_GRAY8,
+            SWS_BILINEAR
+        )
+        .is_err());
+    }
+}